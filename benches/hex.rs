@@ -0,0 +1,104 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hexutil::unstable::{
+    decode_hex, decode_hex_constant_time, decode_hex_options, encode_hex, encode_hex_constant_time,
+    Options,
+};
+
+/// The byte-at-a-time implementation used before the SIMD implementations were added.
+mod scalar {
+    const LOWER: [u8; 16] = *b"0123456789abcdef";
+
+    pub fn encode_hex<'a>(value: &[u8], buffer: &'a mut [u8]) -> &'a str {
+        assert_eq!(value.len() * 2, buffer.len());
+        value
+            .iter()
+            .copied()
+            .zip(buffer.chunks_mut(2))
+            .for_each(|(a, b)| {
+                b[0] = LOWER[usize::from(a >> 4)];
+                b[1] = LOWER[usize::from(a & 0xf)];
+            });
+        core::str::from_utf8(buffer).unwrap()
+    }
+
+    pub fn decode_hex(value: &[u8], buffer: &mut [u8]) -> Result<(), usize> {
+        if value.len() != buffer.len() * 2 {
+            return Err(value.len());
+        }
+        value
+            .chunks(2)
+            .zip(buffer.iter_mut())
+            .enumerate()
+            .try_for_each(|(i, (c, b))| {
+                *b = (from_hex(i * 2, c[0])? << 4) | from_hex(i * 2 + 1, c[1])?;
+                Ok(())
+            })
+    }
+
+    const fn from_hex(i: usize, c: u8) -> Result<u8, usize> {
+        match c {
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'0'..=b'9' => Ok(c - b'0'),
+            _ => Err(i),
+        }
+    }
+}
+
+const SIZES: [usize; 4] = [32, 1024, 64 * 1024, 4 * 1024 * 1024];
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for &len in &SIZES {
+        let value = data(len);
+        let mut buffer = vec![0; len * 2];
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("hexutil", len), &value, |b, value| {
+            b.iter(|| encode_hex(black_box(value), &mut buffer).len())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("constant_time", len),
+            &value,
+            |b, value| b.iter(|| encode_hex_constant_time(black_box(value), &mut buffer).len()),
+        );
+        group.bench_with_input(BenchmarkId::new("scalar", len), &value, |b, value| {
+            b.iter(|| scalar::encode_hex(black_box(value), &mut buffer).len())
+        });
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for &len in &SIZES {
+        let mut hex = vec![0; len * 2];
+        encode_hex(&data(len), &mut hex);
+        let mut buffer = vec![0; len];
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("hexutil", len), &hex, |b, hex| {
+            b.iter(|| decode_hex(black_box(hex), &mut buffer).unwrap())
+        });
+        // strict case checking uses the lookup table instead of SIMD
+        let strict = Options {
+            strict: true,
+            ..Options::DEFAULT
+        };
+        group.bench_with_input(BenchmarkId::new("table", len), &hex, |b, hex| {
+            b.iter(|| decode_hex_options(black_box(hex), &mut buffer, &strict).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("constant_time", len), &hex, |b, hex| {
+            b.iter(|| decode_hex_constant_time(black_box(hex), &mut buffer).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", len), &hex, |b, hex| {
+            b.iter(|| scalar::decode_hex(black_box(hex), &mut buffer).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
@@ -0,0 +1,677 @@
+#![warn(missing_docs, clippy::all, clippy::pedantic, clippy::nursery)]
+
+//! Derive macro for the [`hexutil`](https://docs.rs/hexutil) crate.
+//!
+//! Enable the `derive` feature of `hexutil` and use `#[derive(hexutil::Hex)]` instead of
+//! calling the `impl_hex` macro by hand. The `hexutil::hex!` macro decodes hexadecimal
+//! literals at compile time.
+
+extern crate proc_macro;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use std::{convert::TryFrom, env, fs, ops::Range, path::Path};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    Data, DeriveInput, Error, Fields, GenericParam, Ident, LitStr, Member, Result, Token, Type,
+};
+
+/// Implement common traits for binary representable data.
+///
+/// This generates the same code as the `impl_hex` macro. The struct must have exactly one
+/// field, either a byte array (`[u8; N]`) or a primitive integer (`u8` to `u128`, `i8` to
+/// `i128`). The number of bytes is inferred from the type of the field. Additional
+/// `PhantomData` fields are allowed, which makes this usable with generic types.
+///
+/// ```
+/// # use hexutil::{FromHex, ToHex};
+/// # #[derive(Debug, PartialEq, Eq)]
+/// #[derive(hexutil::Hex)]
+/// struct Test([u8; 4]);
+///
+/// assert_eq!(Test(*b"\x12\x34\x56\x78").to_string(), "12345678");
+/// assert_eq!(Test::from_hex(b"12345678").unwrap(), Test(*b"\x12\x34\x56\x78"));
+/// ```
+///
+/// # Attributes
+/// Attribute | Description
+/// -|-
+/// `#[hex(be)]` | Encode an integer in big-endian byte order (default)
+/// `#[hex(le)]` | Encode an integer in little-endian byte order
+/// `#[hex(presets(...))]` | The presets to derive (defaults to `default`)
+///
+/// ```
+/// # #[derive(Debug, PartialEq, Eq)]
+/// #[derive(hexutil::Hex)]
+/// #[hex(le, presets(Display, serde, group(1, ':')))]
+/// struct Test {
+///     value: u16,
+/// }
+///
+/// assert_eq!(Test { value: 0x1234 }.to_string(), "34:12");
+/// assert_eq!(serde_json::to_string(&Test { value: 0x1234 }).unwrap(), r#""3412""#);
+/// ```
+///
+/// ```
+/// # use hexutil::ToHex;
+/// use std::marker::PhantomData;
+///
+/// trait Purpose {}
+///
+/// #[derive(hexutil::Hex)]
+/// struct Key<P: Purpose>([u8; 2], PhantomData<P>);
+///
+/// # struct Signing;
+/// # impl Purpose for Signing {}
+/// let key: Key<Signing> = "abcd".parse().unwrap();
+/// assert_eq!(key.to_hex(), "abcd");
+/// ```
+#[proc_macro_derive(Hex, attributes(hex))]
+pub fn derive_hex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Decode a hexadecimal literal at compile time.
+///
+/// `hex!("...")` expands to a byte array (`[u8; N]`), which can be used in `const` items.
+/// An optional `0x` prefix and whitespace are ignored, both lowercase and uppercase digits are
+/// accepted:
+///
+/// ```
+/// const MAGIC: [u8; 4] = hexutil::hex!("deadBEEF");
+///
+/// assert_eq!(MAGIC, [0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(hexutil::hex!("0x0102 0304"), [1, 2, 3, 4]);
+/// ```
+///
+/// `hex!(Type, "...")` creates a value of a type implementing `FromHex` using its
+/// `from_binary_bytes` function. The value is created at runtime (trait functions cannot be
+/// called in `const` items), but the options of the type are honoured: the length, the `0x`
+/// prefix and the case of the digits (`strict`) are checked at compile time. A `checksum` is
+/// verified when the value is created (panics if invalid) and a `reversed` byte order is
+/// applied. Whitespace is still ignored:
+///
+/// ```
+/// # #[derive(Debug, PartialEq, Eq)]
+/// struct Hash([u8; 4]);
+///
+/// hexutil::impl_hex!(Hash, 4, |&self| &self.0, |data| Ok(Self(data)));
+///
+/// assert_eq!(hexutil::hex!(Hash, "01234567"), Hash([0x01, 0x23, 0x45, 0x67]));
+///
+/// # #[derive(Debug, PartialEq, Eq)]
+/// struct Address([u8; 4]);
+///
+/// hexutil::impl_hex!(Address, 4, |&self| &self.0, |data| Ok(Self(data)), [
+///     default,
+///     prefix,
+///     strict,
+/// ]);
+///
+/// assert_eq!(hexutil::hex!(Address, "0x0123 abcd"), Address([0x01, 0x23, 0xab, 0xcd]));
+/// ```
+///
+/// Invalid literals are rejected at compile time (the error marks the invalid character):
+///
+/// ```compile_fail
+/// let value = hexutil::hex!("12g4");
+/// ```
+///
+/// ```compile_fail
+/// # struct Hash([u8; 4]);
+/// # hexutil::impl_hex!(Hash, 4, |&self| &self.0, |data| Ok(Self(data)));
+/// let value = hexutil::hex!(Hash, "012345");
+/// ```
+///
+/// ```compile_fail
+/// # struct Address([u8; 4]);
+/// # hexutil::impl_hex!(Address, 4, |&self| &self.0, |data| Ok(Self(data)), [default, prefix]);
+/// // missing 0x prefix
+/// let value = hexutil::hex!(Address, "0123abcd");
+/// ```
+///
+/// ```compile_fail
+/// # struct Address([u8; 4]);
+/// # hexutil::impl_hex!(Address, 4, |&self| &self.0, |data| Ok(Self(data)), [default, strict]);
+/// // uppercase hex digit in strict mode
+/// let value = hexutil::hex!(Address, "0123ABCD");
+/// ```
+///
+/// ```should_panic
+/// # struct Address([u8; 20]);
+/// # hexutil::impl_hex!(Address, 20, |&self| &self.0, |data| Ok(Self(data)), [default, checksum]);
+/// // invalid checksum
+/// let value = hexutil::hex!(Address, "5AAEB6053F3E94C9b9A09f33669435E7Ef1BeAed");
+/// ```
+#[proc_macro]
+pub fn hex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as HexLiteral);
+    input
+        .expand()
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Include a file of hexadecimal digits as a byte array (`&'static [u8; N]`).
+///
+/// Unlike `include_bytes!` and `include_str!`, the path is relative to the directory containing
+/// the `Cargo.toml` of the calling crate (`CARGO_MANIFEST_DIR`), not to the current file.
+/// Whitespace, line breaks and comments (starting with `#` or `//` until the end of the line)
+/// are ignored. Both lowercase and uppercase digits are accepted. Invalid characters are
+/// reported with their line and column at compile time.
+///
+/// ```
+/// // fixtures/example.hex:
+/// // # example firmware
+/// // 0123 4567 // header
+/// // 89ab cdef
+/// const FIRMWARE: &[u8; 8] = hexutil::include_hex!("fixtures/example.hex");
+///
+/// assert_eq!(FIRMWARE, &[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+/// ```
+#[proc_macro]
+pub fn include_hex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    include_file(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let options = Options::parse(input)?;
+    let (member, ty, construct) = data_field(input)?;
+    let ident = &input.ident;
+    let presets = &options.presets;
+
+    let (len, to_hex, from_hex) = match ty {
+        Type::Array(array) if is_u8(&array.elem) => {
+            let len = &array.len;
+            (
+                quote!(#len),
+                quote!(|&self| &self.#member),
+                quote!(|data| Ok(#construct)),
+            )
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let prim = path
+                .path
+                .get_ident()
+                .and_then(|ident| integer_len(ident).map(|len| (ident, len)));
+            let (prim, len) = prim.ok_or_else(|| unsupported(ty))?;
+            let len = Literal::usize_unsuffixed(len);
+            let (to_bytes, from_bytes) = options.endian.methods();
+            (
+                quote!(#len),
+                quote!(|self| self.#member.#to_bytes()),
+                quote!(|data| {
+                    let data = #prim::#from_bytes(data);
+                    Ok(#construct)
+                }),
+            )
+        }
+        _ => return Err(unsupported(ty)),
+    };
+
+    let mut generics = input.generics.clone();
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type(param) => param.default = None,
+            GenericParam::Const(param) => param.default = None,
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    let params = &generics.params;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let predicates = where_clause.map(|where_clause| &where_clause.predicates);
+
+    Ok(quote! {
+        ::hexutil::impl_hex!(
+            impl[#params] #ident #ty_generics where [#predicates],
+            #len,
+            #to_hex,
+            #from_hex,
+            [#presets],
+        );
+    })
+}
+
+/// Find the field containing the data and build the expression creating `Self` from `data`.
+///
+/// All other fields must be `PhantomData` markers.
+fn data_field(input: &DeriveInput) -> Result<(Member, &Type, TokenStream)> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`Hex` can only be derived for structs",
+            ))
+        }
+    };
+    let mut data = None;
+    let mut values = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = field
+            .ident
+            .clone()
+            .map_or_else(|| Member::Unnamed(i.into()), Member::Named);
+        if is_phantom_data(&field.ty) {
+            values.push((member.clone(), quote!(::core::marker::PhantomData)));
+        } else if data.is_none() {
+            values.push((member.clone(), quote!(data)));
+            data = Some((member, &field.ty));
+        } else {
+            return Err(Error::new_spanned(
+                field,
+                "`Hex` requires exactly one field that is not `PhantomData`",
+            ));
+        }
+    }
+    let (member, ty) = data.ok_or_else(|| {
+        Error::new(
+            if matches!(fields, Fields::Unit) {
+                input.ident.span()
+            } else {
+                fields.span()
+            },
+            "`Hex` requires exactly one field that is not `PhantomData`",
+        )
+    })?;
+    let construct = if let Fields::Named(_) = fields {
+        let (members, values): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+        quote!(Self { #(#members: #values),* })
+    } else {
+        let values = values.iter().map(|(_, value)| value);
+        quote!(Self(#(#values),*))
+    };
+    Ok((member, ty, construct))
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none()
+        && path.path.segments.last().is_some_and(|segment| segment.ident == "PhantomData"))
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8"))
+}
+
+fn integer_len(ident: &Ident) -> Option<usize> {
+    Some(match ident.to_string().as_str() {
+        "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" => 4,
+        "u64" | "i64" => 8,
+        "u128" | "i128" => 16,
+        _ => return None,
+    })
+}
+
+fn unsupported(ty: &Type) -> Error {
+    Error::new_spanned(
+        ty,
+        "`Hex` requires a field of type `[u8; N]` or a primitive integer",
+    )
+}
+
+/// The input of the `hex!` macro.
+struct HexLiteral {
+    ty: Option<Type>,
+    lit: LitStr,
+}
+
+impl Parse for HexLiteral {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ty = if input.peek(LitStr) {
+            None
+        } else {
+            let ty = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(ty)
+        };
+        let lit = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { ty, lit })
+    }
+}
+
+impl HexLiteral {
+    fn expand(&self) -> Result<TokenStream> {
+        let value = self.lit.value();
+        let (prefix, digits) = split_prefix(&value);
+        let bytes = decode_literal(&self.lit, prefix.len(), digits)?;
+        let len = bytes.len();
+        let bytes = bytes.into_iter().map(Literal::u8_suffixed);
+        let array = if len == 0 {
+            quote!([0u8; 0])
+        } else {
+            quote!([#(#bytes),*])
+        };
+        let Some(ty) = &self.ty else {
+            return Ok(array);
+        };
+        let name = quote!(#ty).to_string().replace(' ', "");
+        let message = format!("invalid length of `{name}`: found {len} bytes");
+        let literal: String = prefix
+            .chars()
+            .chain(digits.chars().filter(|c| !c.is_whitespace()))
+            .collect();
+        let checks = literal_checks(&self.lit, prefix, digits);
+        Ok(quote! {{
+            const {
+                ::core::assert!(
+                    <#ty as ::hexutil::unstable::FromHexCore>::LENGTH.contains(#len),
+                    #message,
+                );
+                match ::hexutil::unstable::check_hex_literal(
+                    #literal,
+                    &<#ty as ::hexutil::unstable::FromHexCore>::OPTIONS,
+                ) {
+                    ::core::result::Result::Ok(()) => {}
+                    #checks
+                }
+            }
+            ::hexutil::unstable::from_hex_literal::<#ty>(#literal, &#array)
+        }})
+    }
+}
+
+/// The match arms reporting the errors of `check_hex_literal` at the offending character.
+///
+/// The options of the type are only known in a `const` context, so a (marked) message is
+/// prepared for every possible error.
+fn literal_checks(lit: &LitStr, prefix: &str, digits: &str) -> TokenStream {
+    let first = |f: fn(&char) -> bool| {
+        digits
+            .char_indices()
+            .find(|(_, c)| f(c))
+            .map(|(i, _)| prefix.len() + i)
+    };
+    let errors = [
+        (
+            "MissingPrefix",
+            // the prefix is uppercase or missing (marking the first digit)
+            if prefix.is_empty() {
+                first(|_| true)
+            } else {
+                Some(1)
+            },
+            if prefix.is_empty() {
+                "missing 0x prefix"
+            } else {
+                "expected a lowercase 0x prefix"
+            },
+        ),
+        ("UnexpectedPrefix", Some(1), "unexpected 0x prefix"),
+        (
+            "Uppercase",
+            first(char::is_ascii_uppercase),
+            "uppercase hex digit in strict mode",
+        ),
+        (
+            "Lowercase",
+            first(char::is_ascii_lowercase),
+            "lowercase hex digit in strict mode",
+        ),
+    ];
+    errors
+        .iter()
+        .map(|&(variant, index, description)| {
+            let variant = Ident::new(variant, Span::call_site());
+            let (span, message) = index.map_or_else(
+                || (lit.span(), description.to_owned()),
+                |index| mark_character(lit, index, description),
+            );
+            let panic = quote_spanned!(span=> ::core::panic!("{}", #message));
+            quote! {
+                ::core::result::Result::Err(::hexutil::unstable::LiteralError::#variant) => #panic,
+            }
+        })
+        .collect()
+}
+
+/// Split a `hex!` literal into its `0x` prefix (if any) and the digits.
+fn split_prefix(value: &str) -> (&str, &str) {
+    if value.starts_with("0x") || value.starts_with("0X") {
+        value.split_at(2)
+    } else {
+        value.split_at(0)
+    }
+}
+
+/// Decode the `digits` of a `hex!` literal starting at byte `offset` of its value.
+fn decode_literal(lit: &LitStr, offset: usize, digits: &str) -> Result<Vec<u8>> {
+    decode_digits(digits, false).map_err(|err| match err {
+        DigitsError::InvalidCharacter { index, .. } => {
+            invalid_literal_character(lit, offset + index)
+        }
+        DigitsError::OddLength(digits) => {
+            Error::new(lit.span(), format!("odd number of hex digits: {digits}"))
+        }
+    })
+}
+
+/// Report the invalid `character` at byte `index` of the value of the literal.
+///
+/// The position is located in the source code of the literal (escapes are taken into account)
+/// and marked below it, as `Span::subspan` is not supported on stable compilers.
+fn invalid_literal_character(lit: &LitStr, index: usize) -> Error {
+    let (span, message) = mark_character(lit, index, "invalid hex character");
+    Error::new(span, message)
+}
+
+/// Describe the character at byte `index` of the value of the literal.
+///
+/// Returns the span of the character (if supported by the compiler) and a message containing
+/// its position in the source code of the literal, marked below the line containing it.
+fn mark_character(lit: &LitStr, index: usize, description: &str) -> (Span, String) {
+    let token = lit.token();
+    let source = token.to_string();
+    let value = lit.value();
+    let character = value[index..].chars().next().unwrap_or_default();
+    let chars = value[..index].chars().count();
+    let range = source_ranges(&source).and_then(|ranges| ranges.get(chars).cloned());
+    let Some(range) = range else {
+        return (
+            lit.span(),
+            format!("{description} at {index}: {character:?}"),
+        );
+    };
+    let position = source[..range.start].chars().count() - source.find('"').map_or(0, |i| i + 1);
+    let line_start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[range.start..]
+        .find('\n')
+        .map_or(source.len(), |i| range.start + i);
+    let column = source[line_start..range.start].chars().count();
+    let message = format!(
+        "{description} at {position}: {character:?}\n{}\n{:column$}^ here",
+        &source[line_start..line_end],
+        "",
+    );
+    (token.subspan(range).unwrap_or_else(|| lit.span()), message)
+}
+
+/// The byte ranges in the `source` code of a string literal of all characters of its value.
+///
+/// Returns `None` if the literal could not be parsed.
+fn source_ranges(source: &str) -> Option<Vec<Range<usize>>> {
+    let mut ranges = Vec::new();
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.find('"')?;
+        let start = 1 + hashes + 1;
+        let end = source.len().checked_sub(1 + hashes)?;
+        for (i, c) in source.get(start..end)?.char_indices() {
+            ranges.push(start + i..start + i + c.len_utf8());
+        }
+        return Some(ranges);
+    }
+    let end = source.len().checked_sub(1)?;
+    let mut chars = source.get(..end)?.char_indices().skip(1).peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            ranges.push(start..start + c.len_utf8());
+            continue;
+        }
+        let (_, escape) = chars.next()?;
+        match escape {
+            'x' => {
+                chars.next()?;
+                chars.next()?;
+            }
+            'u' => while chars.next()?.1 != '}' {},
+            '\n' => {
+                // a line continuation skips the following whitespace
+                while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+                continue;
+            }
+            _ => {}
+        }
+        let end = chars.peek().map_or(end, |&(i, _)| i);
+        ranges.push(start..end);
+    }
+    Some(ranges)
+}
+
+/// Read and decode the file of an `include_hex!` macro.
+fn include_file(lit: &LitStr) -> Result<TokenStream> {
+    let dir = env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or_else(|| Error::new(lit.span(), "`CARGO_MANIFEST_DIR` is not set"))?;
+    let path = Path::new(&dir).join(lit.value());
+    let text = fs::read_to_string(&path).map_err(|err| {
+        Error::new(
+            lit.span(),
+            format!("couldn't read `{}`: {err}", path.display()),
+        )
+    })?;
+    let bytes = decode_digits(&text, true).map_err(|err| match err {
+        DigitsError::InvalidCharacter { index, character } => {
+            let (line, column) = line_column(&text, index);
+            Error::new(
+                lit.span(),
+                format!(
+                    "invalid hex character at {}:{line}:{column}: {character:?}",
+                    lit.value()
+                ),
+            )
+        }
+        DigitsError::OddLength(digits) => Error::new(
+            lit.span(),
+            format!("odd number of hex digits in {}: {digits}", lit.value()),
+        ),
+    })?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| Error::new(lit.span(), "the path is not valid UTF-8"))?;
+    let array = if bytes.is_empty() {
+        quote!([0u8; 0])
+    } else {
+        let bytes = bytes.into_iter().map(Literal::u8_suffixed);
+        quote!([#(#bytes),*])
+    };
+    Ok(quote! {{
+        // rebuild if the file changes
+        const _: &[u8] = ::core::include_bytes!(#path);
+        &#array
+    }})
+}
+
+/// An error while decoding hexadecimal digits.
+enum DigitsError {
+    /// An invalid character at byte `index`.
+    InvalidCharacter { index: usize, character: char },
+
+    /// An odd number of digits.
+    OddLength(usize),
+}
+
+/// Decode hexadecimal digits ignoring whitespace (and `#` or `//` line comments if enabled).
+fn decode_digits(text: &str, comments: bool) -> std::result::Result<Vec<u8>, DigitsError> {
+    let mut nibbles = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, character)) = chars.next() {
+        if character.is_whitespace() {
+            continue;
+        }
+        let comment =
+            character == '#' || (character == '/' && matches!(chars.peek(), Some((_, '/'))));
+        if comments && comment {
+            while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            continue;
+        }
+        let nibble = character
+            .to_digit(16)
+            .and_then(|nibble| u8::try_from(nibble).ok());
+        let Some(nibble) = nibble else {
+            return Err(DigitsError::InvalidCharacter { index, character });
+        };
+        nibbles.push(nibble);
+    }
+    if nibbles.len() % 2 != 0 {
+        return Err(DigitsError::OddLength(nibbles.len()));
+    }
+    Ok(nibbles.chunks(2).map(|n| (n[0] << 4) | n[1]).collect())
+}
+
+/// The line and column (both starting at 1) of the byte `index` in `text`.
+fn line_column(text: &str, index: usize) -> (usize, usize) {
+    let before = &text[..index];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    (line, before[line_start..].chars().count() + 1)
+}
+
+enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    fn methods(&self) -> (Ident, Ident) {
+        let (to, from) = match self {
+            Self::Big => ("to_be_bytes", "from_be_bytes"),
+            Self::Little => ("to_le_bytes", "from_le_bytes"),
+        };
+        (
+            Ident::new(to, Span::call_site()),
+            Ident::new(from, Span::call_site()),
+        )
+    }
+}
+
+struct Options {
+    endian: Endian,
+    presets: TokenStream,
+}
+
+impl Options {
+    fn parse(input: &DeriveInput) -> Result<Self> {
+        let mut endian = Endian::Big;
+        let mut presets = None;
+        for attr in &input.attrs {
+            if !attr.path().is_ident("hex") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("be") {
+                    endian = Endian::Big;
+                } else if meta.path.is_ident("le") {
+                    endian = Endian::Little;
+                } else if meta.path.is_ident("presets") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    presets = Some(content.parse()?);
+                } else {
+                    return Err(meta.error("unknown `hex` attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        let presets = presets.unwrap_or_else(|| quote!(default));
+        Ok(Self { endian, presets })
+    }
+}
@@ -0,0 +1,143 @@
+use crate::FromHexError;
+use core::fmt::{self, Write};
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+impl FromHexError {
+    /// Render this error with a caret-underlined snippet of the `input`.
+    ///
+    /// The `input` must be the value passed to the parsing function.
+    #[must_use]
+    pub const fn snippet<'a>(&'a self, input: &'a str) -> Snippet<'a> {
+        Snippet { error: self, input }
+    }
+
+    /// Attach the `input` to this error to create a `miette::Diagnostic`.
+    ///
+    /// ```
+    /// # use hexutil::ParseHex;
+    /// use miette::Diagnostic;
+    ///
+    /// let err = "12g4".parse_hex::<[u8; 2]>().err().unwrap();
+    /// let err = err.with_source("12g4");
+    /// let label = err.labels().unwrap().next().unwrap();
+    /// assert_eq!(label.offset(), 2);
+    /// assert_eq!(label.len(), 1);
+    /// ```
+    pub fn with_source(self, input: impl Into<String>) -> SourceError {
+        SourceError {
+            error: self,
+            input: input.into(),
+        }
+    }
+
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::InvalidHexCharacter { .. } => "not a hexadecimal digit",
+            Self::InvalidCase { .. } => "not in the canonical case",
+            Self::MissingPrefix => "expected `0x` here",
+            _ => "here",
+        }
+    }
+}
+
+/// A parse error rendered with a caret-underlined snippet of the input.
+///
+/// Created by `FromHexError::snippet`.
+#[derive(Debug, Clone, Copy)]
+pub struct Snippet<'a> {
+    error: &'a FromHexError,
+    input: &'a str,
+}
+
+impl fmt::Display for Snippet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        let span = match self.error.span() {
+            Some(span) if self.input.is_char_boundary(span.start) => span,
+            _ => return Ok(()),
+        };
+        let input = self.input;
+        let line_start = input[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[span.start..]
+            .find('\n')
+            .map_or(input.len(), |i| span.start + i);
+        let line = input[line_start..line_end].trim_end_matches('\r');
+        let line_number = input[..line_start].matches('\n').count() + 1;
+        let before = &input[line_start..span.start];
+        let width = input
+            .get(span.start..span.end.min(line_end))
+            .map_or(1, |s| s.chars().count().max(1));
+
+        let gutter = line_number.to_string().len();
+        write!(
+            f,
+            "\n{:gutter$}--> {line_number}:{}\n",
+            "",
+            before.chars().count() + 1
+        )?;
+        write!(f, "{:gutter$} |\n{line_number} | {line}\n", "")?;
+        write!(f, "{:gutter$} | ", "")?;
+        // keep tabs to align the carets with the snippet
+        for c in before.chars() {
+            f.write_char(if c == '\t' { '\t' } else { ' ' })?;
+        }
+        for _ in 0..width {
+            f.write_char('^')?;
+        }
+        Ok(())
+    }
+}
+
+/// A parse error together with its input, implementing `miette::Diagnostic`.
+///
+/// Created by `FromHexError::with_source`.
+#[derive(Debug)]
+pub struct SourceError {
+    error: FromHexError,
+    input: String,
+}
+
+impl SourceError {
+    /// The underlying error.
+    #[must_use]
+    pub const fn error(&self) -> &FromHexError {
+        &self.error
+    }
+
+    /// The input that caused the error.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Render the error with a caret-underlined snippet of the input.
+    #[must_use]
+    pub fn snippet(&self) -> Snippet<'_> {
+        self.error.snippet(&self.input)
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for SourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Diagnostic for SourceError {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.input)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let span = self.error.span()?;
+        let span = span.start..span.end.min(self.input.len());
+        let label = LabeledSpan::new_with_span(Some(self.error.label().into()), span);
+        Some(Box::new(core::iter::once(label)))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    unstable::{self, Checksum, Options},
+    ToHex,
+};
+use core::fmt;
+
+/// Format a value with custom options (see [`ToHex::hex_fmt`]).
+///
+/// ```
+/// # use hexutil::ToHex;
+/// let value = [0xab, 0xcd, 0xef, 0x01];
+///
+/// assert_eq!(value.hex_fmt().sep(':').to_string(), "ab:cd:ef:01");
+/// assert_eq!(value.hex_fmt().group(2).to_string(), "abcd ef01");
+/// assert_eq!(value.hex_fmt().sep(':').upper().to_string(), "AB:CD:EF:01");
+/// assert_eq!(format!("{:.4}", value.hex_fmt().sep(':')), "ab:cd");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexFmt<'a, T: ?Sized> {
+    value: &'a T,
+    options: Options,
+}
+
+impl<'a, T> HexFmt<'a, T>
+where
+    T: ?Sized + ToHex,
+{
+    pub(crate) const fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            options: T::OPTIONS,
+        }
+    }
+
+    /// Split the digits into groups of `bytes` bytes (separated by a space by default).
+    #[must_use]
+    pub const fn group(mut self, bytes: usize) -> Self {
+        self.options.group = bytes;
+        self
+    }
+
+    /// Separate the groups by `separator` (groups of one byte if not set otherwise).
+    #[must_use]
+    pub const fn sep(mut self, separator: char) -> Self {
+        if self.options.group == 0 {
+            self.options.group = 1;
+        }
+        self.options.separator = separator;
+        self
+    }
+
+    /// Use uppercase hexadecimal digits.
+    #[must_use]
+    pub const fn upper(mut self) -> Self {
+        self.options.uppercase = true;
+        self.options.checksum = Checksum::Disabled;
+        self
+    }
+
+    /// Use lowercase hexadecimal digits.
+    #[must_use]
+    pub const fn lower(mut self) -> Self {
+        self.options.uppercase = false;
+        self.options.checksum = Checksum::Disabled;
+        self
+    }
+
+    /// Add (or remove) the `0x` prefix.
+    #[must_use]
+    pub const fn prefix(mut self, prefix: bool) -> Self {
+        self.options.emit_prefix = prefix;
+        self
+    }
+}
+
+impl<T> fmt::Display for HexFmt<'_, T>
+where
+    T: ?Sized + ToHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        unstable::fmt_display_options(self.value, &self.options, f)
+    }
+}
@@ -0,0 +1,144 @@
+//! Implementations for byte containers and primitive integers.
+
+use crate::{
+    unstable::{FromHexCore, Length, ToHexCore},
+    FromHex, FromHexError, ToHex,
+};
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{boxed::Box, vec, vec::Vec};
+
+impl<const N: usize> ToHexCore for [u8; N] {
+    type Bytes = ();
+    type Buffer = [[u8; 2]; N];
+    fn create_buffer(_len: usize) -> Self::Buffer {
+        [[0; 2]; N]
+    }
+    fn buffer_as_bytes(buffer: &mut Self::Buffer) -> &mut [u8] {
+        buffer.as_flattened_mut()
+    }
+    fn to_binary_bytes(&self) -> Self::Bytes {}
+    fn binary_bytes_as_mut(_bytes: &mut Self::Bytes) -> &mut [u8] {
+        &mut []
+    }
+    fn as_binary_bytes(&self, _bytes: &Self::Bytes) -> &[u8] {
+        self
+    }
+}
+impl<const N: usize> ToHex for [u8; N] {}
+
+impl<const N: usize> FromHexCore for [u8; N] {
+    type Bytes = Self;
+    type Error = FromHexError;
+    const LENGTH: Length = Length::exact(N);
+    const TYPE_NAME: &'static str = "byte array";
+    fn create_bytes(_len: Option<usize>) -> Self::Bytes {
+        [0; N]
+    }
+    fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+        bytes
+    }
+    fn from_binary_bytes(bytes: Self::Bytes) -> Result<Self, Self::Error> {
+        Ok(bytes)
+    }
+}
+impl<const N: usize> FromHex for [u8; N] {}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_dyn_to_hex {
+    ($($ty:ty),*) => {$(
+        impl ToHexCore for $ty {
+            type Bytes = ();
+            type Buffer = Vec<u8>;
+            fn create_buffer(len: usize) -> Self::Buffer {
+                vec![0; len]
+            }
+            fn buffer_as_bytes(buffer: &mut Self::Buffer) -> &mut [u8] {
+                buffer
+            }
+            fn to_binary_bytes(&self) -> Self::Bytes {}
+            fn binary_bytes_as_mut(_bytes: &mut Self::Bytes) -> &mut [u8] {
+                &mut []
+            }
+            fn as_binary_bytes(&self, _bytes: &Self::Bytes) -> &[u8] {
+                self
+            }
+        }
+        impl ToHex for $ty {}
+    )*};
+}
+
+#[cfg(feature = "alloc")]
+impl_dyn_to_hex!([u8], Vec<u8>, Box<[u8]>);
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_dyn_from_hex {
+    ($($ty:ty),*) => {$(
+        impl FromHexCore for $ty {
+            type Bytes = Vec<u8>;
+            type Error = FromHexError;
+            const TYPE_NAME: &'static str = stringify!($ty);
+            fn create_bytes(len: Option<usize>) -> Self::Bytes {
+                vec![0; len.unwrap_or(0)]
+            }
+            fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+                bytes
+            }
+            fn from_binary_bytes(bytes: Self::Bytes) -> Result<Self, Self::Error> {
+                Ok(bytes.into())
+            }
+        }
+        impl FromHex for $ty {}
+    )*};
+}
+
+#[cfg(feature = "alloc")]
+impl_dyn_from_hex!(Vec<u8>, Box<[u8]>);
+
+/// Integers are encoded in big-endian byte order (most significant byte first).
+macro_rules! impl_integer {
+    ($($ty:ident: $len:expr),*) => {$(
+        impl ToHexCore for $ty {
+            type Bytes = [u8; $len];
+            type Buffer = [u8; $len * 2];
+            fn create_buffer(_len: usize) -> Self::Buffer {
+                [0; $len * 2]
+            }
+            fn buffer_as_bytes(buffer: &mut Self::Buffer) -> &mut [u8] {
+                buffer
+            }
+            fn to_binary_bytes(&self) -> Self::Bytes {
+                self.to_be_bytes()
+            }
+            fn binary_bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+                bytes
+            }
+            fn as_binary_bytes<'a>(&'a self, bytes: &'a Self::Bytes) -> &'a [u8] {
+                bytes
+            }
+        }
+        impl ToHex for $ty {}
+
+        impl FromHexCore for $ty {
+            type Bytes = [u8; $len];
+            type Error = FromHexError;
+            const LENGTH: Length = Length::exact($len);
+            const TYPE_NAME: &'static str = stringify!($ty);
+            fn create_bytes(_len: Option<usize>) -> Self::Bytes {
+                [0; $len]
+            }
+            fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+                bytes
+            }
+            fn from_binary_bytes(bytes: Self::Bytes) -> Result<Self, Self::Error> {
+                Ok(Self::from_be_bytes(bytes))
+            }
+        }
+        impl FromHex for $ty {}
+    )*};
+}
+
+impl_integer!(
+    u8: 1, u16: 2, u32: 4, u64: 8, u128: 16,
+    i8: 1, i16: 2, i32: 4, i64: 8, i128: 16
+);
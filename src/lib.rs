@@ -80,10 +80,31 @@
 //! `default` | `convert` and `serde`
 //! `convert` | `Display` and `FromStr`
 //! `Display` | Implement the `std::fmt::Display` trait (enables the `to_string()` method)
+//! `LowerHex` | Implement the `std::fmt::LowerHex` trait (`{:x}`, `{:#x}` adds a `0x` prefix)
+//! `UpperHex` | Implement the `std::fmt::UpperHex` trait (`{:X}`, `{:#X}` adds a `0x` prefix)
+//! `Debug` | Implement the `std::fmt::Debug` trait (formatted as `Name(3412)`)
 //! `FromStr` | Implement the `std::convert::FromStr` trait (enables the `str.parse()` method)
 //! `serde` | `Serialize` and `Deserialize`
 //! `Serialize` | Implement the `serde::Serialize` trait
 //! `Deserialize` | Implement the `serde::Deserialize` trait
+//! `uppercase` | Use uppercase hexadecimal digits (`Display`, `ToHex` and `Serialize`)
+//! `prefix` or `prefix(required)` | Add a `0x` prefix (`Display`, `ToHex` and `Serialize`) and require it while parsing
+//! `prefix(optional)` | Add a `0x` prefix (`Display`, `ToHex` and `Serialize`), but accept it as optional while parsing
+//! `optional_prefix` | Accept an optional `0x` prefix while parsing (without adding it)
+//! `lenient` | Ignore whitespace and separators (`:`, `-` and `_`) while parsing
+//! `lenient(b"...")` | Like `lenient`, but ignore the given separators instead (e.g. `lenient(b".")`)
+//! `strict` | Only accept the canonical representation while parsing: digits in the canonical case (lowercase, or uppercase with `uppercase`) and a lowercase `0x` prefix only with `prefix` (cannot be combined with `lenient` or an optional prefix)
+//! `constant_time` | Encode and decode without secret-dependent branches or table lookups (for secret values, cannot be combined with `lenient` or a checksum)
+//! `redacted` | Implement `Display` and `Debug` without revealing the value, and an `expose_hex` method passing the full hexadecimal digits to a closure
+//! `redacted(n)` | Like `redacted`, but show the first `n` hexadecimal digits (at most half of them)
+//! `ConstantTimeEq` | Implement the `subtle::ConstantTimeEq` trait and a `ct_eq_hex` method comparing with an untrusted hexadecimal string (requires the `subtle` feature)
+//! `PartialEq` | Implement the `PartialEq` trait in constant time (requires the `subtle` feature)
+//! `zeroize` | Wipe the temporary binary and hexadecimal buffers after use, also if parsing fails (requires the `zeroize` feature)
+//! `reversed` | Reverse the byte order of the hexadecimal representation (the binary representation is not affected)
+//! `checksum` | Use an EIP-55 mixed-case checksum (`Display`, `ToHex` and `Serialize`) and verify it while parsing
+//! `optional_checksum` | Like `checksum`, but accept all-lowercase and all-uppercase input without verification
+//! `error(Type)` | Return `Type` instead of `FromHexError` from `FromHex`, `FromStr` and `ParseHex` (must implement `From<FromHexError>`)
+//! `group(n, sep)` | Split the `Display` output into groups of `n` bytes separated by `sep` (defaults to `' '`)
 //!
 //! Derive only the `ToHex`, `FromHex`, `Serialize` and `Deserialize` traits:
 //! ```
@@ -92,6 +113,361 @@
 //! hexutil::impl_hex!(Test, 42, |self| self.0, |data| Ok(Self(data)), [serde]);
 //! ```
 //!
+//! Implement the formatting traits:
+//! ```
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)), [
+//!     default, LowerHex, UpperHex, Debug
+//! ]);
+//!
+//! let test = Test([0xab, 0xcd]);
+//! assert_eq!(format!("{:x}", test), "abcd");
+//! assert_eq!(format!("{:#X}", test), "0xABCD");
+//! assert_eq!(format!("{:?}", test), "Test(abcd)");
+//!
+//! #[derive(Debug)]
+//! struct Outer {
+//!     test: Test,
+//! }
+//! assert_eq!(format!("{:?}", Outer { test }), "Outer { test: Test(abcd) }");
+//! ```
+//!
+//! The formatting traits support width, fill, alignment and precision. The precision
+//! truncates the output to the given number of hexadecimal digits. Zero-padding is only
+//! supported by `LowerHex` and `UpperHex` (`Display` ignores it like for strings):
+//! ```
+//! # struct Test([u8; 4]);
+//! # hexutil::impl_hex!(Test, 4, |self| self.0, |data| Ok(Self(data)), [default, LowerHex]);
+//! let test = Test([0x12, 0x34, 0x56, 0x78]);
+//! assert_eq!(format!("{:.4}", test), "1234");
+//! assert_eq!(format!("[{:>10}]", test), "[  12345678]");
+//! assert_eq!(format!("[{:-^12.6}]", test), "[---123456---]");
+//! assert_eq!(format!("{:#012x}", test), "0x0012345678");
+//! assert_eq!(format!("[{:010}]", test), "[12345678  ]");
+//! ```
+//!
+//! Use uppercase hexadecimal digits:
+//! ```
+//! # use hexutil::ToHex;
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)), [default, uppercase]);
+//!
+//! let test = Test([0xab, 0xcd]);
+//! assert_eq!(test.to_string(), "ABCD");
+//! assert_eq!(test.to_hex(), "ABCD");
+//! assert_eq!(serde_json::to_string(&test).unwrap(), r#""ABCD""#);
+//! ```
+//!
+//! Handle a `0x` prefix:
+//! ```
+//! # use hexutil::{FromHex, FromHexError, ToHex};
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)), [default, prefix]);
+//!
+//! let test: Test = "0xabcd".parse().unwrap();
+//! assert_eq!(test.to_string(), "0xabcd");
+//! assert_eq!(test.to_hex(), "0xabcd");
+//! assert_eq!(serde_json::to_string(&test).unwrap(), r#""0xabcd""#);
+//! assert!(matches!(Test::from_hex(b"abcd"), Err(FromHexError::MissingPrefix)));
+//! assert!(matches!(
+//!     Test::from_hex(b"0xabgd"),
+//!     Err(FromHexError::InvalidHexCharacter { index: 4, character: 'g', .. })
+//! ));
+//!
+//! struct Optional([u8; 2]);
+//!
+//! hexutil::impl_hex!(Optional, 2, |self| self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     optional_prefix
+//! ]);
+//!
+//! assert_eq!("0xabcd".parse::<Optional>().unwrap().to_string(), "abcd");
+//! assert_eq!("abcd".parse::<Optional>().unwrap().to_string(), "abcd");
+//!
+//! // e.g. for Ethereum values
+//! struct Emitted([u8; 2]);
+//!
+//! hexutil::impl_hex!(Emitted, 2, |self| self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     prefix(optional)
+//! ]);
+//!
+//! assert_eq!("0xabcd".parse::<Emitted>().unwrap().to_string(), "0xabcd");
+//! assert_eq!("abcd".parse::<Emitted>().unwrap().to_string(), "0xabcd");
+//! ```
+//!
+//! Ignore whitespace and separators while parsing:
+//! ```
+//! # use hexutil::{FromHex, FromHexError};
+//! # #[derive(Debug)]
+//! struct Test([u8; 4]);
+//!
+//! hexutil::impl_hex!(Test, 4, |self| self.0, |data| Ok(Self(data)), [default, lenient]);
+//!
+//! let test: Test = "AB:CD:EF:01".parse().unwrap();
+//! assert_eq!(test.to_string(), "abcdef01");
+//! let test: Test = serde_json::from_str(r#""abcd ef01\n""#).unwrap();
+//! assert_eq!(test.to_string(), "abcdef01");
+//! assert!(matches!(
+//!     Test::from_hex(b"ab-cd-eg-01"),
+//!     Err(FromHexError::InvalidHexCharacter { index: 7, character: 'g', .. })
+//! ));
+//! assert!(matches!(Test::from_hex(b"ab-cd-ef"), Err(FromHexError::InvalidLength { actual: 6, .. })));
+//!
+//! struct Dotted([u8; 4]);
+//!
+//! hexutil::impl_hex!(Dotted, 4, |self| self.0, |data| Ok(Self(data)), [default, lenient(b".")]);
+//!
+//! let dotted: Dotted = "ab.cd ef.01".parse().unwrap();
+//! assert_eq!(dotted.to_string(), "abcdef01");
+//! assert!("ab:cd:ef:01".parse::<Dotted>().is_err());
+//! ```
+//!
+//! Group the output of `Display` (combine with `lenient` to parse the grouped output):
+//! ```
+//! # use hexutil::ToHex;
+//! struct Test([u8; 4]);
+//!
+//! hexutil::impl_hex!(Test, 4, |self| self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     uppercase,
+//!     group(1, ':'),
+//!     lenient,
+//! ]);
+//!
+//! let test: Test = "AB:CD:EF:01".parse().unwrap();
+//! assert_eq!(test.to_string(), "AB:CD:EF:01");
+//! assert_eq!(test.to_hex(), "ABCDEF01");
+//! ```
+//!
+//! Accept only the canonical case while parsing (the hexadecimal representation is unique):
+//! ```
+//! # use hexutil::{FromHex, FromHexError};
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)), [default, strict]);
+//!
+//! assert_eq!("abcd".parse::<Test>().unwrap().to_string(), "abcd");
+//! assert!(matches!(Test::from_hex(b"abCd"), Err(FromHexError::InvalidCase { index: 2, character: 'C', .. })));
+//!
+//! struct Upper([u8; 2]);
+//!
+//! hexutil::impl_hex!(Upper, 2, |self| self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     uppercase,
+//!     strict,
+//! ]);
+//!
+//! assert_eq!("ABCD".parse::<Upper>().unwrap().to_string(), "ABCD");
+//! assert!(matches!(Upper::from_hex(b"ABcD"), Err(FromHexError::InvalidCase { index: 2, character: 'c', .. })));
+//!
+//! // parse-only types use `uppercase` to select the canonical case
+//! struct UpperInput([u8; 2]);
+//!
+//! hexutil::impl_from_hex!(UpperInput, 2, |data| Ok(Self(data)), [default, uppercase, strict]);
+//!
+//! assert_eq!("ABCD".parse::<UpperInput>().unwrap().0, [0xab, 0xcd]);
+//! assert!("abcd".parse::<UpperInput>().is_err());
+//!
+//! struct Prefixed([u8; 2]);
+//!
+//! hexutil::impl_hex!(Prefixed, 2, |self| self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     prefix,
+//!     strict,
+//! ]);
+//!
+//! assert_eq!("0xabcd".parse::<Prefixed>().unwrap().to_string(), "0xabcd");
+//! assert!(matches!(Prefixed::from_hex(b"0Xabcd"), Err(FromHexError::MissingPrefix)));
+//! ```
+//!
+//! The `strict` preset cannot be combined with `lenient` or an optional prefix:
+//! ```compile_fail
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)), [default, strict, lenient]);
+//!
+//! let test: Test = "ab:cd".parse().unwrap();
+//! ```
+//!
+//! Encode and decode secret values in constant time (`Display`, `FromStr`, serde, `ToHex` and
+//! `FromHex`). The position of an invalid character is only searched for after the whole input
+//! has been processed:
+//! ```
+//! # use hexutil::{ParseHex, ToHex};
+//! struct SecretKey([u8; 4]);
+//!
+//! hexutil::impl_hex!(SecretKey, 4, |&self| &self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     constant_time,
+//! ]);
+//!
+//! let key: SecretKey = "0123abCD".parse().unwrap();
+//! assert_eq!(key.0, [0x01, 0x23, 0xab, 0xcd]);
+//! assert_eq!(key.to_hex(), "0123abcd");
+//! assert!("0123abcg".parse::<SecretKey>().is_err());
+//! // separators are never ignored in constant-time mode
+//! assert!("0123:abcd".parse_hex_lenient::<SecretKey>(b":").is_err());
+//! ```
+//!
+//! The `constant_time` preset cannot be combined with `lenient` or a checksum, which are not
+//! implemented in constant time:
+//! ```compile_fail
+//! struct SecretKey([u8; 4]);
+//!
+//! hexutil::impl_hex!(SecretKey, 4, |&self| &self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     constant_time,
+//!     lenient,
+//! ]);
+//!
+//! let key: SecretKey = "0123abcd".parse().unwrap();
+//! ```
+//!
+//! Keep secret values out of logs. `Display` and `Debug` are redacted, but parsing, serde and
+//! `ToHex` still use the full representation:
+//! ```
+//! # use hexutil::ToHex;
+//! struct ApiKey([u8; 4]);
+//!
+//! hexutil::impl_hex!(ApiKey, 4, |&self| &self.0, |data| Ok(Self(data)), [
+//!     redacted(4),
+//!     FromStr,
+//!     serde,
+//! ]);
+//!
+//! let key: ApiKey = "a1b2c3d4".parse().unwrap();
+//! assert_eq!(key.to_string(), "a1b2\u{2026}[redacted]");
+//! assert_eq!(format!("{:?}", key), "ApiKey(a1b2\u{2026}[redacted])");
+//! assert_eq!(format!("{:>17}", key), "  a1b2\u{2026}[redacted]");
+//! key.expose_hex(|hex| assert_eq!(hex, "a1b2c3d4"));
+//! assert_eq!(serde_json::to_string(&key).unwrap(), r#""a1b2c3d4""#);
+//!
+//! struct Token([u8; 4]);
+//!
+//! hexutil::impl_hex!(Token, 4, |&self| &self.0, |data| Ok(Self(data)), [redacted, FromStr]);
+//!
+//! let token: Token = "a1b2c3d4".parse().unwrap();
+//! assert_eq!(format!("{} {:?}", token, token), "[redacted] Token([redacted])");
+//!
+//! struct Pin([u8; 2]);
+//!
+//! hexutil::impl_hex!(Pin, 2, |&self| &self.0, |data| Ok(Self(data)), [redacted(8), FromStr]);
+//!
+//! let pin: Pin = "1234".parse().unwrap();
+//! assert_eq!(pin.to_string(), "12\u{2026}[redacted]");
+//! ```
+//!
+//! With the `subtle` feature enabled, values can be compared in constant time. The `ct_eq_hex`
+//! method compares with a hexadecimal string without decoding it first:
+//! ```
+//! # #[cfg(not(feature = "subtle"))]
+//! # fn main() {}
+//! # #[cfg(feature = "subtle")]
+//! # fn main() {
+//! use subtle::ConstantTimeEq;
+//!
+//! #[derive(Debug, Eq)]
+//! struct Mac([u8; 4]);
+//!
+//! hexutil::impl_hex!(Mac, 4, |&self| &self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     ConstantTimeEq,
+//!     PartialEq,
+//! ]);
+//!
+//! let mac = Mac([0xa1, 0xb2, 0xc3, 0xd4]);
+//! assert!(bool::from(mac.ct_eq(&Mac([0xa1, 0xb2, 0xc3, 0xd4]))));
+//! assert_eq!(mac, "a1b2c3d4".parse().unwrap());
+//! assert_ne!(mac, "a1b2c3d5".parse().unwrap());
+//! assert!(bool::from(mac.ct_eq_hex("A1B2c3d4")));
+//! assert!(!bool::from(mac.ct_eq_hex("a1b2c3d5")));
+//! assert!(!bool::from(mac.ct_eq_hex("a1b2c3dg")));
+//! assert!(!bool::from(mac.ct_eq_hex("a1b2c3")));
+//! # }
+//! ```
+//!
+//! With the `zeroize` feature enabled, the `zeroize` preset wipes the temporary binary and
+//! hexadecimal buffers used by `Display`, `ToHex`, `FromHex` and serde (the preset is rejected
+//! without the feature). The bytes passed to the `from_hex` function are owned by it, also if
+//! it returns an error, and have to be wiped by it:
+//! ```
+//! # #[cfg(feature = "zeroize")]
+//! # fn main() {
+//! use hexutil::FromHexError;
+//! use zeroize::Zeroize;
+//!
+//! struct SecretKey([u8; 32]);
+//!
+//! hexutil::impl_hex!(SecretKey, 32, |&self| &self.0, |data| {
+//!     let mut data = data;
+//!     if data == [0; 32] {
+//!         data.zeroize();
+//!         return Err(FromHexError::InvalidValue);
+//!     }
+//!     Ok(Self(data))
+//! }, [default, constant_time, zeroize]);
+//!
+//! assert!("00".repeat(32).parse::<SecretKey>().is_err());
+//! assert!("01".repeat(32).parse::<SecretKey>().is_ok());
+//! # }
+//! # #[cfg(not(feature = "zeroize"))]
+//! # fn main() {}
+//! ```
+//!
+//! Reverse the byte order of the hexadecimal representation (e.g. for Bitcoin hashes). Binary
+//! serde formats still use the original byte order:
+//! ```
+//! # use hexutil::ToHex;
+//! struct Txid([u8; 4]);
+//!
+//! hexutil::impl_hex!(Txid, 4, |&self| &self.0, |data| Ok(Self(data)), [default, reversed]);
+//!
+//! let txid: Txid = "12345678".parse().unwrap();
+//! assert_eq!(txid.0, [0x78, 0x56, 0x34, 0x12]);
+//! assert_eq!(txid.to_string(), "12345678");
+//! assert_eq!(txid.to_hex(), "12345678");
+//! assert_eq!(serde_json::to_string(&txid).unwrap(), r#""12345678""#);
+//! assert_eq!(bincode::serialize(&txid).unwrap(), [0x78, 0x56, 0x34, 0x12]);
+//! ```
+//!
+//! Use an EIP-55 mixed-case checksum (e.g. for Ethereum addresses):
+//! ```
+//! # use hexutil::{FromHex, FromHexError};
+//! struct Address([u8; 20]);
+//!
+//! hexutil::impl_hex!(Address, 20, |&self| &self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     prefix,
+//!     checksum,
+//! ]);
+//!
+//! let address: Address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".parse().unwrap();
+//! assert_eq!(address.to_string(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+//! assert!(matches!(
+//!     Address::from_hex(b"0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+//!     Err(FromHexError::InvalidChecksum)
+//! ));
+//!
+//! struct Optional([u8; 20]);
+//!
+//! hexutil::impl_hex!(Optional, 20, |&self| &self.0, |data| Ok(Self(data)), [
+//!     default,
+//!     prefix,
+//!     optional_checksum,
+//! ]);
+//!
+//! let address: Optional = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359".parse().unwrap();
+//! assert_eq!(address.to_string(), "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359");
+//! assert!(matches!(
+//!     Optional::from_hex(b"0xfb6916095ca1df60bB79Ce92cE3Ea74c37c5d359"),
+//!     Err(FromHexError::InvalidChecksum)
+//! ));
+//! ```
+//!
 //! # `FromHex` Error
 //! The second function returns a `Result<Self, FromHexError>`:
 //! ```
@@ -112,6 +488,66 @@
 //! ));
 //! ```
 //!
+//! Use the `error` preset to return a custom error type. It must implement
+//! `From<FromHexError>` (and `Display` for the `Deserialize` preset):
+//! ```
+//! # use hexutil::{FromHex, FromHexError};
+//! #[derive(Debug)]
+//! enum KeyError {
+//!     Hex(FromHexError),
+//!     Zero,
+//! }
+//!
+//! impl From<FromHexError> for KeyError {
+//!     fn from(err: FromHexError) -> Self {
+//!         Self::Hex(err)
+//!     }
+//! }
+//!
+//! impl std::fmt::Display for KeyError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+//!         match self {
+//!             Self::Hex(err) => err.fmt(f),
+//!             Self::Zero => f.write_str("the key must not be zero"),
+//!         }
+//!     }
+//! }
+//!
+//! struct Key([u8; 2]);
+//!
+//! hexutil::impl_hex!(Key, 2, |self| self.0, |data| if data == [0; 2] {
+//!     Err(KeyError::Zero)
+//! } else {
+//!     Ok(Self(data))
+//! }, [default, error(KeyError)]);
+//!
+//! assert!(matches!(Key::from_hex(b"0000"), Err(KeyError::Zero)));
+//! assert!(matches!("12".parse::<Key>(), Err(KeyError::Hex(FromHexError::InvalidLength { .. }))));
+//! let err = serde_json::from_str::<Key>(r#""0000""#).err().unwrap();
+//! assert_eq!(err.to_string(), "the key must not be zero at line 1 column 6");
+//! ```
+//!
+//! Parsing errors describe the expected length and the position of invalid characters:
+//! ```
+//! # use hexutil::FromHex;
+//! struct Test([u8; 2]);
+//!
+//! hexutil::impl_hex!(Test, 2, |self| self.0, |data| Ok(Self(data)));
+//!
+//! let err = Test::from_hex(b"123456").err().unwrap();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "invalid length of Test: expected 2 bytes (4 hex digits), found 6 hex digits"
+//! );
+//! let err = "12\u{e4}".parse::<Test>().err().unwrap();
+//! assert_eq!(err.to_string(), "invalid hex character at 2: '\u{e4}'");
+//! let err = serde_json::from_str::<Test>(r#""12""#).err().unwrap();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "invalid length 2, expected 4 hex digits for Test at line 1 column 4"
+//! );
+//! ```
+//!
 //! # One direction only
 //! You can also implement only one direction:
 //! ```
@@ -124,25 +560,173 @@
 //!
 //! hexutil::impl_from_hex!(Test, 42, |data| Ok(Self(data)));
 //! ```
+//!
+//! # Byte containers and integers
+//! `ToHex` and `FromHex` are implemented for byte arrays (`[u8; N]`) and all primitive
+//! integers. With the `alloc` feature `ToHex` is also implemented for `[u8]`, `Vec<u8>` and
+//! `Box<[u8]>` and `FromHex` for `Vec<u8>` and `Box<[u8]>`.
+//!
+//! Integers are encoded in big-endian byte order (most significant byte first), so the
+//! hexadecimal representation matches the usual way of writing numbers:
+//! ```
+//! # use hexutil::{ParseHex, ToHex};
+//! assert_eq!([0x12, 0x34].to_hex(), "1234");
+//! assert_eq!(vec![0xab, 0xcd].to_hex(), "abcd");
+//! assert_eq!(0x1234_u16.to_hex(), "1234");
+//! assert_eq!((-2_i8).to_hex(), "fe");
+//!
+//! let bytes: [u8; 2] = "1234".parse_hex().unwrap();
+//! assert_eq!(bytes, [0x12, 0x34]);
+//! let bytes: Vec<u8> = "123456".parse_hex().unwrap();
+//! assert_eq!(bytes, [0x12, 0x34, 0x56]);
+//! let value: u32 = "00001234".parse_hex().unwrap();
+//! assert_eq!(value, 0x1234);
+//! ```
+//!
+//! # Generic types
+//! The type can be any path (e.g. `crate::model::Hash`). Generic parameters, lifetimes and
+//! bounds are declared in brackets after `impl`, an optional `where` clause follows the type:
+//! ```
+//! # use hexutil::ToHex;
+//! use std::marker::PhantomData;
+//!
+//! trait Purpose {}
+//!
+//! struct Key<P: Purpose>([u8; 4], PhantomData<P>);
+//!
+//! hexutil::impl_hex!(impl[P: Purpose] Key<P>, 4, |&self| &self.0, |data| Ok(Self(
+//!     data,
+//!     PhantomData
+//! )));
+//!
+//! struct Id<'a, T>(&'a [u8; 2], T);
+//!
+//! hexutil::impl_to_hex!(impl['a, T] Id<'a, T> where [T: Copy], 2, |&self| self.0);
+//!
+//! # struct Signing;
+//! # impl Purpose for Signing {}
+//! let key: Key<Signing> = "12345678".parse().unwrap();
+//! assert_eq!(key.to_hex(), "12345678");
+//! assert_eq!(Id(&[0xab, 0xcd], ()).to_string(), "abcd");
+//! ```
+//!
+//! # Dynamic length
+//! Use the `impl_hex_dyn` macro for types with a variable number of bytes (requires the `alloc`
+//! feature). The first function returns a reference to the bytes and the second function
+//! receives a `Vec<u8>`. The number of bytes can be restricted using `min`, `max` and
+//! `multiple_of` (in this order):
+//! ```
+//! # use hexutil::{FromHex, FromHexError, ToHex};
+//! # #[derive(Debug, PartialEq, Eq)]
+//! struct Test(Vec<u8>);
+//!
+//! hexutil::impl_hex_dyn!(Test, min = 2, max = 8, multiple_of = 2, |&self| &self.0, |data| Ok(
+//!     Self(data)
+//! ));
+//!
+//! let test = Test::from_hex(b"12345678").unwrap();
+//! assert_eq!(test, Test(vec![0x12, 0x34, 0x56, 0x78]));
+//! assert_eq!(test.to_hex(), "12345678");
+//!
+//! assert!(matches!(Test::from_hex(b"12"), Err(FromHexError::InvalidLength { actual: 2, .. })));
+//! assert!(matches!(Test::from_hex(b"123456"), Err(FromHexError::InvalidLength { actual: 6, .. })));
+//!
+//! // binary formats use a length prefix
+//! let bin = bincode::serialize(&test).unwrap();
+//! assert_eq!(bin, [4, 0, 0, 0, 0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78]);
+//! assert_eq!(bincode::deserialize::<Test>(&bin).unwrap(), test);
+//! assert!(bincode::deserialize::<Test>(&[1, 0, 0, 0, 0, 0, 0, 0, 0x12]).is_err());
+//! ```
+//!
+//! # Derive
+//! With the `derive` feature enabled, `#[derive(hexutil::Hex)]` can be used instead of `impl_hex`.
+//! The number of bytes is inferred from the single field of the struct (`[u8; N]` or a primitive integer):
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # mod test {
+//! #[derive(hexutil::Hex)]
+//! #[hex(le, presets(serde, Display))]
+//! struct Test(u128);
+//! # }
+//! ```
+//!
+//! The `hex!` macro decodes hexadecimal literals at compile time, either to a byte array or
+//! (with a type) using the `FromHex` implementation and the options of the type:
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # mod test {
+//! const MAGIC: [u8; 4] = hexutil::hex!("deadbeef");
+//! # }
+//! ```
+//!
+//! Files of hexadecimal digits (e.g. test fixtures) can be included using
+//! `include_hex!("path")`, which ignores whitespace and comments (`#` or `//`). Unlike
+//! `include_bytes!` and `include_str!`, the path is relative to the directory containing the
+//! `Cargo.toml` of the calling crate (`CARGO_MANIFEST_DIR`), not to the current file.
+//!
+//! Without the `derive` feature, `unstable::const_decode` and `unstable::const_encode` can be
+//! used in `const` items. They do not know the options of a type, use
+//! `unstable::const_decode_reversed` for types with the `reversed` preset:
+//! ```
+//! const MAGIC: [u8; 4] = hexutil::unstable::const_decode("deadbeef");
+//! ```
+//!
+//! # Diagnostics
+//! The byte range of an invalid input is available using `FromHexError::span`. With the
+//! `diagnostics` feature enabled, errors can be rendered with a caret-underlined snippet of the
+//! input, and `FromHexError::with_source` creates a `miette::Diagnostic`:
+//! ```
+//! # #[cfg(not(feature = "diagnostics"))]
+//! # fn main() {}
+//! # #[cfg(feature = "diagnostics")]
+//! # fn main() {
+//! # use hexutil::ParseHex;
+//! let input = "0123456789abcdef0123456789abcdef01234g6789abcdef0123456789abcdef";
+//! let err = input.parse_hex::<[u8; 32]>().err().unwrap();
+//! assert_eq!(err.snippet(input).to_string(), "\
+//! invalid hex character at 37: 'g'
+//!  --> 1:38
+//!   |
+//! 1 | 0123456789abcdef0123456789abcdef01234g6789abcdef0123456789abcdef
+//!   |                                      ^");
+//! # }
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+mod hex_fmt;
+mod impls;
 mod macros;
 
 pub mod unstable;
 
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{Snippet, SourceError};
+pub use hex_fmt::HexFmt;
+#[cfg(feature = "derive")]
+pub use hexutil_derive::{hex, include_hex, Hex};
+
 #[doc(hidden)]
 pub mod private {
     pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    #[cfg(feature = "subtle")]
+    pub use subtle;
+
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    pub use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "std")]
+    pub use std::{string::String, vec::Vec};
 }
 
-use err_derive::Error;
+use core::ops::Range;
 
 #[cfg(all(feature = "alloc", not(feature = "std")))]
 extern crate alloc;
 
 #[cfg(all(feature = "alloc", not(feature = "std")))]
-use alloc::string::{String, ToString};
+use alloc::string::String;
 
 /// A type that can be converted to a hexadecimal representation.
 pub trait ToHex: unstable::ToHexCore {
@@ -157,11 +741,52 @@ pub trait ToHex: unstable::ToHexCore {
     /// assert_eq!(Test(0x1234).to_hex(), "3412");
     /// ```
     #[cfg(feature = "alloc")]
-    fn to_hex(&self) -> String where {
-        unstable::with_hex_str(self, ToString::to_string)
+    fn to_hex(&self) -> String {
+        unstable::with_hex_str(self, |s| hex_string(Self::OPTIONS.prefix_str(), s))
+    }
+
+    /// Get an uppercase hexadecimal representation.
+    ///
+    /// ```
+    /// # use hexutil::ToHex;
+    /// struct Test(u16);
+    ///
+    /// hexutil::impl_to_hex!(Test, 2, |self| self.0.to_le_bytes());
+    ///
+    /// assert_eq!(Test(0xabcd).to_hex_upper(), "CDAB");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn to_hex_upper(&self) -> String {
+        let mut options = Self::OPTIONS;
+        options.uppercase = true;
+        options.checksum = unstable::Checksum::Disabled;
+        unstable::with_hex_str_options(self, &options, |s| hex_string(options.prefix_str(), s))
+    }
+
+    /// Format this value with custom options (e.g. grouped and separated).
+    ///
+    /// ```
+    /// # use hexutil::ToHex;
+    /// struct Test([u8; 4]);
+    ///
+    /// hexutil::impl_to_hex!(Test, 4, |self| self.0);
+    ///
+    /// let test = Test([0xab, 0xcd, 0xef, 0x01]);
+    /// assert_eq!(test.hex_fmt().group(2).sep(' ').to_string(), "abcd ef01");
+    /// ```
+    fn hex_fmt(&self) -> HexFmt<'_, Self> {
+        HexFmt::new(self)
     }
 }
 
+#[cfg(feature = "alloc")]
+fn hex_string(prefix: &str, hex: &str) -> String {
+    let mut s = String::with_capacity(prefix.len() + hex.len());
+    s.push_str(prefix);
+    s.push_str(hex);
+    s
+}
+
 /// A type that can be created from a hexadecimal representation.
 pub trait FromHex: unstable::FromHexCore {
     /// Try to create an instance of this type from a hexadeccimal representation.
@@ -176,19 +801,8 @@ pub trait FromHex: unstable::FromHexCore {
     /// let test = Test::from_hex(b"3412").unwrap();
     /// assert_eq!(test, Test(0x1234));
     /// ```
-    fn from_hex(buf: &[u8]) -> Result<Self, FromHexError> {
-        if buf.len() & 1 != 0 {
-            return Err(FromHexError::InvalidLength(buf.len()));
-        }
-        let mut bytes = Self::create_bytes(Some(buf.len() / 2));
-        {
-            let bytes = Self::bytes_as_mut(&mut bytes);
-            if bytes.len() * 2 != buf.len() {
-                return Err(FromHexError::InvalidLength(buf.len()));
-            }
-            unstable::decode_hex(buf, bytes)?;
-        }
-        Self::from_binary_bytes(bytes)
+    fn from_hex(buf: &[u8]) -> Result<Self, Self::Error> {
+        unstable::from_hex_options(buf, &Self::OPTIONS)
     }
 }
 
@@ -206,42 +820,178 @@ pub trait FromHex: unstable::FromHexCore {
 /// ```
 pub trait ParseHex {
     /// Parse the hexadecimal string representation and create a value of type `T`.
-    fn parse_hex<T: FromHex>(self) -> Result<T, FromHexError>;
+    fn parse_hex<T: FromHex>(self) -> Result<T, T::Error>;
+
+    /// Parse the hexadecimal string representation ignoring whitespace and `separators`.
+    ///
+    /// The positions of invalid characters are relative to the original input. Types using the
+    /// `strict` or `constant_time` presets do not accept whitespace and separators.
+    ///
+    /// ```
+    /// # use hexutil::ParseHex;
+    /// let bytes: [u8; 4] = "AB:CD ef-01".parse_hex_lenient(b":-").unwrap();
+    /// assert_eq!(bytes, [0xab, 0xcd, 0xef, 0x01]);
+    ///
+    /// // the separators can be read at runtime
+    /// let separators = String::from(".");
+    /// let bytes: [u8; 2] = "ab.cd".parse_hex_lenient(separators.as_bytes()).unwrap();
+    /// assert_eq!(bytes, [0xab, 0xcd]);
+    /// ```
+    fn parse_hex_lenient<T: FromHex>(self, separators: &[u8]) -> Result<T, T::Error>;
+
+    /// Parse the hexadecimal string representation accepting only the canonical case.
+    ///
+    /// Only lowercase digits are accepted (uppercase digits if `T` uses the `uppercase` preset).
+    ///
+    /// ```
+    /// # use hexutil::{FromHexError, ParseHex};
+    /// let bytes: [u8; 2] = "abcd".parse_hex_strict().unwrap();
+    /// assert_eq!(bytes, [0xab, 0xcd]);
+    /// assert!(matches!(
+    ///     "abCd".parse_hex_strict::<[u8; 2]>(),
+    ///     Err(FromHexError::InvalidCase { index: 2, character: 'C', .. })
+    /// ));
+    /// ```
+    fn parse_hex_strict<T: FromHex>(self) -> Result<T, T::Error>;
 }
 
 impl ParseHex for &str {
-    fn parse_hex<T: FromHex>(self) -> Result<T, FromHexError> {
+    fn parse_hex<T: FromHex>(self) -> Result<T, T::Error> {
         self.as_bytes().parse_hex()
     }
+
+    fn parse_hex_lenient<T: FromHex>(self, separators: &[u8]) -> Result<T, T::Error> {
+        self.as_bytes().parse_hex_lenient(separators)
+    }
+
+    fn parse_hex_strict<T: FromHex>(self) -> Result<T, T::Error> {
+        self.as_bytes().parse_hex_strict()
+    }
 }
 
 impl ParseHex for &[u8] {
-    fn parse_hex<T: FromHex>(self) -> Result<T, FromHexError> {
+    fn parse_hex<T: FromHex>(self) -> Result<T, T::Error> {
         T::from_hex(self)
     }
+
+    fn parse_hex_lenient<T: FromHex>(self, separators: &[u8]) -> Result<T, T::Error> {
+        let mut options = T::OPTIONS;
+        options.lenient = true;
+        unstable::from_hex_separators(self, &options, separators)
+    }
+
+    fn parse_hex_strict<T: FromHex>(self) -> Result<T, T::Error> {
+        let mut options = T::OPTIONS;
+        options.strict = true;
+        unstable::from_hex_options(self, &options)
+    }
 }
 
-/// An error occured while converting from a hexadecimal value.
-#[derive(Debug, Error)]
-pub enum FromHexError {
-    /// The number of bytes is not valid.
-    #[error(display = "invalid number of bytes: {}", 0)]
-    InvalidLength(usize),
+pub use error::FromHexError;
 
-    /// The carachter at the given index is invalid.
-    #[error(display = "invalid hex character at {}: {:?}", 0, char::from(1))]
-    InvalidHexCharacter(usize, u8),
+// `err_derive` generates its impls inside of a named constant
+#[allow(non_local_definitions)]
+mod error {
+    use crate::unstable;
+    use err_derive::Error;
 
-    /// The value is not valid.
-    #[error(display = "invalid value")]
-    InvalidValue,
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    use alloc::string::String;
 
-    /// A custom error (static string reference).
-    #[error(display = "{}", 0)]
-    CustomStr(&'static str),
+    /// An error occured while converting from a hexadecimal value.
+    #[derive(Debug, Error)]
+    pub enum FromHexError {
+        /// The number of hexadecimal digits is not valid.
+        #[error(
+            display = "invalid length of {}: expected {}, found {} hex digits",
+            name,
+            expected,
+            actual
+        )]
+        InvalidLength {
+            /// The name of the target type.
+            name: &'static str,
 
-    /// A custom error (`String` requires `alloc` or `std` feature).
-    #[cfg(feature = "alloc")]
-    #[error(display = "{}", 0)]
-    CustomString(String),
+            /// The allowed number of bytes (see [`Length::digits`](unstable::Length::digits) for the
+            /// allowed number of hexadecimal digits).
+            expected: unstable::Length,
+
+            /// The number of hexadecimal digits found (excluding the prefix and separators).
+            actual: usize,
+        },
+
+        /// The character at the given index is invalid.
+        #[error(display = "invalid hex character at {}: {:?}", index, character)]
+        InvalidHexCharacter {
+            /// The invalid character (`U+FFFD` if the input is not valid UTF-8).
+            character: char,
+
+            /// The index of the character (counted in characters).
+            index: usize,
+
+            /// The byte offset of the character.
+            offset: usize,
+        },
+
+        /// The character at the given index is not in the canonical case (`strict` preset).
+        #[error(
+            display = "invalid case of hex character at {}: {:?}",
+            index,
+            character
+        )]
+        InvalidCase {
+            /// The character in the wrong case.
+            character: char,
+
+            /// The index of the character (counted in characters).
+            index: usize,
+
+            /// The byte offset of the character.
+            offset: usize,
+        },
+
+        /// The required `0x` prefix is missing.
+        #[error(display = "missing 0x prefix")]
+        MissingPrefix,
+
+        /// The EIP-55 mixed-case checksum is not valid.
+        #[error(display = "invalid checksum")]
+        InvalidChecksum,
+
+        /// The value is not valid.
+        #[error(display = "invalid value")]
+        InvalidValue,
+
+        /// A custom error (static string reference).
+        #[error(display = "{}", 0)]
+        CustomStr(&'static str),
+
+        /// A custom error (`String` requires `alloc` or `std` feature).
+        #[cfg(feature = "alloc")]
+        #[error(display = "{}", 0)]
+        CustomString(String),
+    }
+}
+
+impl FromHexError {
+    /// The byte range of the input causing this error (if known).
+    ///
+    /// ```
+    /// # use hexutil::ParseHex;
+    /// let err = "12g4".parse_hex::<[u8; 2]>().err().unwrap();
+    /// assert_eq!(err.span(), Some(2..3));
+    /// ```
+    #[must_use]
+    pub const fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::InvalidHexCharacter {
+                character, offset, ..
+            }
+            | Self::InvalidCase {
+                character, offset, ..
+            } => Some(*offset..*offset + character.len_utf8()),
+            Self::MissingPrefix => Some(0..0),
+            _ => None,
+        }
+    }
 }
@@ -1,47 +1,68 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_impl_from_hex {
-    ($ty:ident, $len:expr, |$data:ident| $from_hex:expr) => {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, $len:expr, |$data:ident| $from_hex:expr, [$($preset:tt)*]) => {
         #[doc(hidden)]
-        impl $crate::unstable::FromHexCore for $ty {
+        impl<$($gen)*> $crate::unstable::FromHexCore for $ty where $($where)* {
             type Bytes = [u8; $len];
+            type Error = $crate::private_error_type!($($preset)*);
+            const LENGTH: $crate::unstable::Length = $crate::unstable::Length::exact($len);
+            const OPTIONS: $crate::unstable::Options = $crate::private_options!($($preset)*);
+            const TYPE_NAME: &'static str = stringify!($ty);
             fn create_bytes(_len: Option<usize>) -> Self::Bytes {
                 [0; $len]
             }
             fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
                 bytes
             }
-            fn from_binary_bytes($data: Self::Bytes) -> Result<Self, $crate::FromHexError> {
+            fn from_binary_bytes($data: Self::Bytes) -> Result<Self, Self::Error> {
                 $from_hex
             }
         }
-        impl $crate::FromHex for $ty {}
+        impl<$($gen)*> $crate::FromHex for $ty where $($where)* {}
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_from_hex_preset {
-    ($ty:ident, default) => {
-        $crate::private_from_hex_preset!($ty, convert);
-        $crate::private_from_hex_preset!($ty, serde);
+    ($header:tt, default) => {
+        $crate::private_from_hex_preset!($header, convert);
+        $crate::private_from_hex_preset!($header, serde);
     };
-    ($ty:ident, convert) => {
-        $crate::private_from_hex_preset!($ty, FromStr);
+    ($header:tt, convert) => {
+        $crate::private_from_hex_preset!($header, FromStr);
     };
-    ($ty:ident, FromStr) => {
-        impl ::core::str::FromStr for $ty {
-            type Err = $crate::FromHexError;
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, FromStr) => {
+        impl<$($gen)*> ::core::str::FromStr for $ty where $($where)* {
+            type Err = <Self as $crate::unstable::FromHexCore>::Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 $crate::ParseHex::parse_hex(s)
             }
         }
     };
-    ($ty:ident, serde) => {
-        $crate::private_from_hex_preset!($ty, Deserialize);
+    ($header:tt, serde) => {
+        $crate::private_from_hex_preset!($header, Deserialize);
     };
-    ($ty:ident, Deserialize) => {
-        impl<'de> $crate::private::Deserialize<'de> for $ty {
+    ($header:tt, LowerHex) => {};
+    ($header:tt, UpperHex) => {};
+    ($header:tt, Debug) => {};
+    ($header:tt, uppercase) => {};
+    ($header:tt, prefix) => {};
+    ($header:tt, prefix($($arg:tt)*)) => {};
+    ($header:tt, optional_prefix) => {};
+    ($header:tt, lenient $(($($arg:tt)*))?) => {};
+    ($header:tt, strict) => {};
+    ($header:tt, reversed) => {};
+    ($header:tt, constant_time) => {};
+    ($header:tt, zeroize) => {};
+    ($header:tt, redacted $(($($arg:tt)*))?) => {};
+    ($header:tt, checksum) => {};
+    ($header:tt, optional_checksum) => {};
+    ($header:tt, error($($arg:tt)*)) => {};
+    ($header:tt, group($($arg:tt)*)) => {};
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, Deserialize) => {
+        impl<'de, $($gen)*> $crate::private::Deserialize<'de> for $ty where $($where)* {
             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
             where
                 D: $crate::private::Deserializer<'de>,
@@ -55,10 +76,10 @@ macro_rules! private_from_hex_preset {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_from_hex_presets {
-    ($ty:ident,) => {};
-    ($ty:ident, $preset:ident, $($presets:ident,)*) => {
-        $crate::private_from_hex_preset!($ty, $preset);
-        $crate::private_from_hex_presets!($ty, $($presets,)*);
+    ($header:tt,) => {};
+    ($header:tt, $preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_from_hex_preset!($header, $preset $(($($arg)*))?);
+        $crate::private_from_hex_presets!($header, $($presets)*);
     };
 }
 
@@ -67,12 +88,23 @@ macro_rules! private_from_hex_presets {
 /// See [crate-level documentation](index.html) for more information.
 #[macro_export]
 macro_rules! impl_from_hex {
-    ($ty:ident, $len:expr, |$data:ident| $from_hex:expr $(,)?) => {
-        $crate::impl_from_hex!($ty, $len, |$data| $from_hex, [default]);
+    (impl [$($gen:tt)*] $ty:ty $(where [$($where:tt)*])?, $($rest:tt)*) => {
+        $crate::private_impl_from_hex_macro!({[$($gen)*] $ty [$($($where)*)?]}, $($rest)*);
     };
-    ($ty:ident, $len:expr, |$data:ident| $from_hex:expr, [$($preset:ident),* $(,)?] $(,)?) => {
-        $crate::private_impl_from_hex!($ty, $len, |$data| $from_hex);
-        $crate::private_metadata!($ty, $len, $($preset,)*);
-        $crate::private_from_hex_presets!($ty, $($preset,)*);
+    ($ty:ty, $($rest:tt)*) => {
+        $crate::private_impl_from_hex_macro!({[] $ty []}, $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_from_hex_macro {
+    ($header:tt, $len:expr, |$data:ident| $from_hex:expr $(,)?) => {
+        $crate::private_impl_from_hex_macro!($header, $len, |$data| $from_hex, [default]);
+    };
+    ($header:tt, $len:expr, |$data:ident| $from_hex:expr, [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?) => {
+        $crate::private_impl_from_hex!($header, $len, |$data| $from_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_metadata!($header, (Some($len), concat!(stringify!($len), " bytes of data")), $($preset $(($($arg)*))?,)*);
+        $crate::private_from_hex_presets!($header, $($preset $(($($arg)*))?,)*);
     };
 }
@@ -1,39 +1,74 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_hex_preset {
-    ($ty:ident, default) => {
-        $crate::private_hex_preset!($ty, convert);
-        $crate::private_hex_preset!($ty, serde);
+    ($header:tt, default) => {
+        $crate::private_hex_preset!($header, convert);
+        $crate::private_hex_preset!($header, serde);
     };
-    ($ty:ident, convert) => {
-        $crate::private_hex_preset!($ty, Display);
-        $crate::private_hex_preset!($ty, FromStr);
+    ($header:tt, convert) => {
+        $crate::private_hex_preset!($header, Display);
+        $crate::private_hex_preset!($header, FromStr);
     };
-    ($ty:ident, Display) => {
-        $crate::private_to_hex_preset!($ty, Display);
+    ($header:tt, Display) => {
+        $crate::private_to_hex_preset!($header, Display);
     };
-    ($ty:ident, FromStr) => {
-        $crate::private_from_hex_preset!($ty, FromStr);
+    ($header:tt, LowerHex) => {
+        $crate::private_to_hex_preset!($header, LowerHex);
     };
-    ($ty:ident, serde) => {
-        $crate::private_hex_preset!($ty, Serialize);
-        $crate::private_hex_preset!($ty, Deserialize);
+    ($header:tt, UpperHex) => {
+        $crate::private_to_hex_preset!($header, UpperHex);
     };
-    ($ty:ident, Serialize) => {
-        $crate::private_to_hex_preset!($ty, Serialize);
+    ($header:tt, Debug) => {
+        $crate::private_to_hex_preset!($header, Debug);
     };
-    ($ty:ident, Deserialize) => {
-        $crate::private_from_hex_preset!($ty, Deserialize);
+    ($header:tt, ConstantTimeEq) => {
+        $crate::private_to_hex_preset!($header, ConstantTimeEq);
+    };
+    ($header:tt, PartialEq) => {
+        $crate::private_to_hex_preset!($header, PartialEq);
+    };
+    ($header:tt, redacted $(($($arg:tt)*))?) => {
+        $crate::private_to_hex_preset!($header, redacted $(($($arg)*))?);
+    };
+    ($header:tt, FromStr) => {
+        $crate::private_from_hex_preset!($header, FromStr);
+    };
+    ($header:tt, serde) => {
+        $crate::private_hex_preset!($header, Serialize);
+        $crate::private_hex_preset!($header, Deserialize);
+    };
+    ($header:tt, Serialize) => {
+        $crate::private_to_hex_preset!($header, Serialize);
+    };
+    ($header:tt, Deserialize) => {
+        $crate::private_from_hex_preset!($header, Deserialize);
+    };
+    ($header:tt, uppercase) => {
+        $crate::private_to_hex_preset!($header, uppercase);
+    };
+    ($header:tt, prefix) => {};
+    ($header:tt, prefix($($arg:tt)*)) => {};
+    ($header:tt, optional_prefix) => {};
+    ($header:tt, lenient $(($($arg:tt)*))?) => {};
+    ($header:tt, strict) => {};
+    ($header:tt, reversed) => {};
+    ($header:tt, constant_time) => {};
+    ($header:tt, zeroize) => {};
+    ($header:tt, checksum) => {};
+    ($header:tt, optional_checksum) => {};
+    ($header:tt, error($($arg:tt)*)) => {};
+    ($header:tt, group($($arg:tt)*)) => {
+        $crate::private_to_hex_preset!($header, group($($arg)*));
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_hex_presets {
-    ($ty:ident,) => {};
-    ($ty:ident, $preset:ident, $($presets:ident,)*) => {
-        $crate::private_hex_preset!($ty, $preset);
-        $crate::private_hex_presets!($ty, $($presets,)*);
+    ($header:tt,) => {};
+    ($header:tt, $preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_hex_preset!($header, $preset $(($($arg)*))?);
+        $crate::private_hex_presets!($header, $($presets)*);
     };
 }
 
@@ -42,22 +77,33 @@ macro_rules! private_hex_presets {
 /// See [crate-level documentation](index.html) for more information.
 #[macro_export]
 macro_rules! impl_hex {
-    ($ty:ident, $len:expr, |$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr $(,)?) => {
-        $crate::impl_hex!($ty, $len, |$self| $to_hex, |$data| $from_hex, [default]);
-    };
-    ($ty:ident, $len:expr, |&$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr $(,)?) => {
-        $crate::impl_hex!($ty, $len, |&$self| $to_hex, |$data| $from_hex, [default]);
-    };
-    ($ty:ident, $len:expr, |$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr, [$($preset:ident),* $(,)?] $(,)?) => {
-        $crate::private_impl_to_hex!($ty, $len, |$self| $to_hex);
-        $crate::private_impl_from_hex!($ty, $len, |$data| $from_hex);
-        $crate::private_metadata!($ty, $len, $($preset,)*);
-        $crate::private_hex_presets!($ty, $($preset,)*);
-    };
-    ($ty:ident, $len:expr, |&$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr, [$($preset:ident),* $(,)?] $(,)?) => {
-        $crate::private_impl_to_hex!($ty, $len, |&$self| $to_hex);
-        $crate::private_impl_from_hex!($ty, $len, |$data| $from_hex);
-        $crate::private_metadata!($ty, $len, $($preset,)*);
-        $crate::private_hex_presets!($ty, $($preset,)*);
+    (impl [$($gen:tt)*] $ty:ty $(where [$($where:tt)*])?, $($rest:tt)*) => {
+        $crate::private_impl_hex_macro!({[$($gen)*] $ty [$($($where)*)?]}, $($rest)*);
+    };
+    ($ty:ty, $($rest:tt)*) => {
+        $crate::private_impl_hex_macro!({[] $ty []}, $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_hex_macro {
+    ($header:tt, $len:expr, |$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr $(,)?) => {
+        $crate::private_impl_hex_macro!($header, $len, |$self| $to_hex, |$data| $from_hex, [default]);
+    };
+    ($header:tt, $len:expr, |&$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr $(,)?) => {
+        $crate::private_impl_hex_macro!($header, $len, |&$self| $to_hex, |$data| $from_hex, [default]);
+    };
+    ($header:tt, $len:expr, |$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr, [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?) => {
+        $crate::private_impl_to_hex!($header, $len, |$self| $to_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_impl_from_hex!($header, $len, |$data| $from_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_metadata!($header, (Some($len), concat!(stringify!($len), " bytes of data")), $($preset $(($($arg)*))?,)*);
+        $crate::private_hex_presets!($header, $($preset $(($($arg)*))?,)*);
+    };
+    ($header:tt, $len:expr, |&$self:ident| $to_hex:expr, |$data:ident| $from_hex:expr, [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?) => {
+        $crate::private_impl_to_hex!($header, $len, |&$self| $to_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_impl_from_hex!($header, $len, |$data| $from_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_metadata!($header, (Some($len), concat!(stringify!($len), " bytes of data")), $($preset $(($($arg)*))?,)*);
+        $crate::private_hex_presets!($header, $($preset $(($($arg)*))?,)*);
     };
 }
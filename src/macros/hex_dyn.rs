@@ -0,0 +1,129 @@
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_to_hex_dyn {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, |&$self:ident| $to_hex:expr, [$($preset:tt)*]) => {
+        #[doc(hidden)]
+        impl<$($gen)*> $crate::unstable::ToHexCore for $ty where $($where)* {
+            type Bytes = ();
+            type Buffer = $crate::private::Vec<u8>;
+            const OPTIONS: $crate::unstable::Options = $crate::private_options!($($preset)*);
+            fn create_buffer(len: usize) -> Self::Buffer {
+                let mut buffer = $crate::private::Vec::new();
+                buffer.resize(len, 0);
+                buffer
+            }
+            fn buffer_as_bytes(buffer: &mut Self::Buffer) -> &mut [u8] {
+                buffer
+            }
+            fn to_binary_bytes(&self) -> Self::Bytes {
+                ()
+            }
+            fn binary_bytes_as_mut(_bytes: &mut Self::Bytes) -> &mut [u8] {
+                &mut []
+            }
+            fn as_binary_bytes(&$self, _bytes: &Self::Bytes) -> &[u8] {
+                $to_hex
+            }
+        }
+        impl<$($gen)*> $crate::ToHex for $ty where $($where)* {}
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_from_hex_dyn {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, $length:expr, |$data:ident| $from_hex:expr, [$($preset:tt)*]) => {
+        #[doc(hidden)]
+        impl<$($gen)*> $crate::unstable::FromHexCore for $ty where $($where)* {
+            type Bytes = $crate::private::Vec<u8>;
+            type Error = $crate::private_error_type!($($preset)*);
+            const LENGTH: $crate::unstable::Length = $length;
+            const OPTIONS: $crate::unstable::Options = $crate::private_options!($($preset)*);
+            const TYPE_NAME: &'static str = stringify!($ty);
+            fn create_bytes(len: Option<usize>) -> Self::Bytes {
+                let mut bytes = $crate::private::Vec::new();
+                bytes.resize(len.unwrap_or(0), 0);
+                bytes
+            }
+            fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+                bytes
+            }
+            fn from_binary_bytes($data: Self::Bytes) -> Result<Self, Self::Error> {
+                $from_hex
+            }
+        }
+        impl<$($gen)*> $crate::FromHex for $ty where $($where)* {}
+    };
+}
+
+/// Implement common traits for binary representable data with a dynamic length.
+///
+/// See [crate-level documentation](index.html) for more information.
+#[macro_export]
+macro_rules! impl_hex_dyn {
+    (impl [$($gen:tt)*] $ty:ty $(where [$($where:tt)*])?, $($rest:tt)*) => {
+        $crate::private_impl_hex_dyn_macro!({[$($gen)*] $ty [$($($where)*)?]}, $($rest)*);
+    };
+    ($ty:ty, $($rest:tt)*) => {
+        $crate::private_impl_hex_dyn_macro!({[] $ty []}, $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_hex_dyn_macro {
+    (
+        $header:tt,
+        $(min = $min:expr,)?
+        $(max = $max:expr,)?
+        $(multiple_of = $multiple_of:expr,)?
+        |&$self:ident| $to_hex:expr,
+        |$data:ident| $from_hex:expr $(,)?
+    ) => {
+        $crate::private_impl_hex_dyn_macro!(
+            $header,
+            $(min = $min,)?
+            $(max = $max,)?
+            $(multiple_of = $multiple_of,)?
+            |&$self| $to_hex,
+            |$data| $from_hex,
+            [default]
+        );
+    };
+    (
+        $header:tt,
+        $(min = $min:expr,)?
+        $(max = $max:expr,)?
+        $(multiple_of = $multiple_of:expr,)?
+        |&$self:ident| $to_hex:expr,
+        |$data:ident| $from_hex:expr,
+        [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?
+    ) => {
+        $crate::private_impl_to_hex_dyn!($header, |&$self| $to_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_impl_from_hex_dyn!(
+            $header,
+            $crate::unstable::Length {
+                $(min: $min,)?
+                $(max: Some($max),)?
+                $(multiple_of: $multiple_of,)?
+                ..$crate::unstable::Length::ANY
+            },
+            |$data| $from_hex,
+            [$($preset $(($($arg)*))?,)*]
+        );
+        $crate::private_metadata!(
+            $header,
+            (
+                None,
+                concat!(
+                    "variable number of bytes of data"
+                    $(, ", at least ", stringify!($min))?
+                    $(, ", at most ", stringify!($max))?
+                    $(, ", a multiple of ", stringify!($multiple_of))?
+                )
+            ),
+            $($preset $(($($arg)*))?,)*
+        );
+        $crate::private_hex_presets!($header, $($preset $(($($arg)*))?,)*);
+    };
+}
@@ -1,25 +1,25 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_metadata {
-    ($ty:ident, $len:expr,) => {};
-    ($ty:ident, $len:expr, default, $($presets:ident,)*) => {
-        $crate::private_metadata!($ty, $len, serde,);
+    ($header:tt, $len:tt,) => {};
+    ($header:tt, $len:tt, default, $($presets:tt)*) => {
+        $crate::private_metadata!($header, $len, serde,);
     };
-    ($ty:ident, $len:expr, serde, $($presets:ident,)*) => {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, ($len:expr, $desc:expr), serde, $($presets:tt)*) => {
         #[doc(hidden)]
-        impl $crate::unstable::serde::Metadata for $ty {
+        impl<$($gen)*> $crate::unstable::serde::Metadata for $ty where $($where)* {
             const NAME: &'static str = stringify!($ty);
-            const EXPECTING: &'static str = concat!("a valid ", stringify!($ty), " (", stringify!($len), " bytes of data)");
-            const LEN: Option<usize> = Some($len);
+            const EXPECTING: &'static str = concat!("a valid ", stringify!($ty), " (", $desc, ")");
+            const LEN: Option<usize> = $len;
         }
     };
-    ($ty:ident, $len:expr, Serialize, $($presets:ident,)*) => {
-        $crate::private_metadata!($ty, $len, serde,);
+    ($header:tt, $len:tt, Serialize, $($presets:tt)*) => {
+        $crate::private_metadata!($header, $len, serde,);
     };
-    ($ty:ident, $len:expr, Deserialize, $($presets:ident,)*) => {
-        $crate::private_metadata!($ty, $len, serde,);
+    ($header:tt, $len:tt, Deserialize, $($presets:tt)*) => {
+        $crate::private_metadata!($header, $len, serde,);
     };
-    ($ty:ident, $len:expr, $preset:ident, $($presets:ident,)*) => {
-        $crate::private_metadata!($ty, $len, $($presets,)*);
+    ($header:tt, $len:tt, $preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_metadata!($header, $len, $($presets)*);
     };
 }
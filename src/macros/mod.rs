@@ -1,4 +1,6 @@
 mod from_hex;
 mod hex;
+mod hex_dyn;
 mod metadata;
+mod options;
 mod to_hex;
@@ -0,0 +1,108 @@
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_options {
+    (@munch $options:expr,) => {
+        $options
+    };
+    (@munch $options:expr, uppercase, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { uppercase: true, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, prefix, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options {
+            prefix: $crate::unstable::Prefix::Required,
+            emit_prefix: true,
+            ..$options
+        }, $($presets)*)
+    };
+    (@munch $options:expr, prefix(required), $($presets:tt)*) => {
+        $crate::private_options!(@munch $options, prefix, $($presets)*)
+    };
+    (@munch $options:expr, prefix(optional), $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options {
+            prefix: $crate::unstable::Prefix::Optional,
+            emit_prefix: true,
+            ..$options
+        }, $($presets)*)
+    };
+    (@munch $options:expr, optional_prefix, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options {
+            prefix: $crate::unstable::Prefix::Optional,
+            ..$options
+        }, $($presets)*)
+    };
+    (@munch $options:expr, lenient, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { lenient: true, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, lenient($separators:expr), $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options {
+            lenient: true,
+            separators: $separators,
+            ..$options
+        }, $($presets)*)
+    };
+    (@munch $options:expr, checksum, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { checksum: $crate::unstable::Checksum::Required, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, optional_checksum, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { checksum: $crate::unstable::Checksum::Optional, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, strict, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { strict: true, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, constant_time, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { constant_time: true, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, zeroize, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { zeroize: $crate::private_zeroize!(), ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, reversed, $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options { reversed: true, ..$options }, $($presets)*)
+    };
+    (@munch $options:expr, group($group:expr $(, $separator:expr)? $(,)?), $($presets:tt)*) => {
+        $crate::private_options!(@munch $crate::unstable::Options {
+            group: $group,
+            $(separator: $separator,)?
+            ..$options
+        }, $($presets)*)
+    };
+    (@munch $options:expr, $preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_options!(@munch $options, $($presets)*)
+    };
+    ($($preset:tt)*) => {
+        $crate::unstable::Options::validated(
+            $crate::private_options!(@munch $crate::unstable::Options::DEFAULT, $($preset)*)
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_error_type {
+    () => {
+        $crate::FromHexError
+    };
+    (error($error:ty), $($presets:tt)*) => {
+        $error
+    };
+    ($preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_error_type!($($presets)*)
+    };
+}
+
+#[cfg(feature = "zeroize")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_zeroize {
+    () => {
+        true
+    };
+}
+
+#[cfg(not(feature = "zeroize"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_zeroize {
+    () => {
+        compile_error!("the `zeroize` preset requires the `zeroize` feature of `hexutil`")
+    };
+}
@@ -1,11 +1,12 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_impl_to_hex {
-    ($ty:ident, $len:expr, |$self:ident| $to_hex:expr) => {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, $len:expr, |$self:ident| $to_hex:expr, [$($preset:tt)*]) => {
         #[doc(hidden)]
-        impl $crate::unstable::ToHexCore for $ty {
+        impl<$($gen)*> $crate::unstable::ToHexCore for $ty where $($where)* {
             type Bytes = [u8; $len];
             type Buffer = [u8; $len * 2];
+            const OPTIONS: $crate::unstable::Options = $crate::private_options!($($preset)*);
             fn create_buffer(_len: usize) -> Self::Buffer {
                 [0; $len * 2]
             }
@@ -15,17 +16,21 @@ macro_rules! private_impl_to_hex {
             fn to_binary_bytes(&$self) -> Self::Bytes {
                 $to_hex
             }
+            fn binary_bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8] {
+                bytes
+            }
             fn as_binary_bytes<'a>(&'a $self, bytes: &'a Self::Bytes) -> &'a [u8] {
                 bytes
             }
         }
-        impl $crate::ToHex for $ty {}
+        impl<$($gen)*> $crate::ToHex for $ty where $($where)* {}
     };
-    ($ty:ident, $len:expr, |&$self:ident| $to_hex:expr) => {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, $len:expr, |&$self:ident| $to_hex:expr, [$($preset:tt)*]) => {
         #[doc(hidden)]
-        impl $crate::unstable::ToHexCore for $ty {
+        impl<$($gen)*> $crate::unstable::ToHexCore for $ty where $($where)* {
             type Bytes = ();
             type Buffer = [u8; $len * 2];
+            const OPTIONS: $crate::unstable::Options = $crate::private_options!($($preset)*);
             fn create_buffer(_len: usize) -> Self::Buffer {
                 [0; $len * 2]
             }
@@ -35,36 +40,115 @@ macro_rules! private_impl_to_hex {
             fn to_binary_bytes(&self) -> Self::Bytes {
                 ()
             }
+            fn binary_bytes_as_mut(_bytes: &mut Self::Bytes) -> &mut [u8] {
+                &mut []
+            }
             fn as_binary_bytes(&$self, _bytes: &Self::Bytes) -> &[u8] {
                 $to_hex
             }
         }
-        impl $crate::ToHex for $ty {}
+        impl<$($gen)*> $crate::ToHex for $ty where $($where)* {}
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_to_hex_preset {
-    ($ty:ident, default) => {
-        $crate::private_to_hex_preset!($ty, convert);
-        $crate::private_to_hex_preset!($ty, serde);
+    ($header:tt, default) => {
+        $crate::private_to_hex_preset!($header, convert);
+        $crate::private_to_hex_preset!($header, serde);
+    };
+    ($header:tt, convert) => {
+        $crate::private_to_hex_preset!($header, Display);
+    };
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, Display) => {
+        impl<$($gen)*> ::core::fmt::Display for $ty where $($where)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                $crate::unstable::fmt_display(self, f)
+            }
+        }
+    };
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, LowerHex) => {
+        impl<$($gen)*> ::core::fmt::LowerHex for $ty where $($where)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                $crate::unstable::fmt_lower_hex(self, f)
+            }
+        }
     };
-    ($ty:ident, convert) => {
-        $crate::private_to_hex_preset!($ty, Display);
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, UpperHex) => {
+        impl<$($gen)*> ::core::fmt::UpperHex for $ty where $($where)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                $crate::unstable::fmt_upper_hex(self, f)
+            }
+        }
     };
-    ($ty:ident, Display) => {
-        impl ::core::fmt::Display for $ty {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, Debug) => {
+        impl<$($gen)*> ::core::fmt::Debug for $ty where $($where)* {
             fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                $crate::unstable::with_hex_str(self, |s| f.write_str(s))
+                $crate::unstable::fmt_debug(self, stringify!($ty), f)
             }
         }
     };
-    ($ty:ident, serde) => {
-        $crate::private_to_hex_preset!($ty, Serialize);
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, ConstantTimeEq) => {
+        impl<$($gen)*> $crate::private::subtle::ConstantTimeEq for $ty where $($where)* {
+            fn ct_eq(&self, other: &Self) -> $crate::private::subtle::Choice {
+                $crate::unstable::ct_eq(self, other)
+            }
+        }
+        impl<$($gen)*> $ty where $($where)* {
+            /// Compare with an untrusted hexadecimal representation in constant time.
+            #[must_use]
+            pub fn ct_eq_hex(&self, hex: &str) -> $crate::private::subtle::Choice {
+                $crate::unstable::ct_eq_hex(self, hex.as_bytes())
+            }
+        }
     };
-    ($ty:ident, Serialize) => {
-        impl $crate::private::Serialize for $ty {
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, PartialEq) => {
+        impl<$($gen)*> ::core::cmp::PartialEq for $ty where $($where)* {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::unstable::ct_eq(self, other).into()
+            }
+        }
+    };
+    ($header:tt, redacted) => {
+        $crate::private_to_hex_preset!($header, redacted(0));
+    };
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, redacted($visible:expr)) => {
+        impl<$($gen)*> ::core::fmt::Display for $ty where $($where)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                $crate::unstable::fmt_redacted(self, $visible, f)
+            }
+        }
+        impl<$($gen)*> ::core::fmt::Debug for $ty where $($where)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                $crate::unstable::fmt_debug_redacted(self, stringify!($ty), $visible, f)
+            }
+        }
+        impl<$($gen)*> $ty where $($where)* {
+            /// Call `f` with the full hexadecimal digits (`Display` and `Debug` are redacted).
+            pub fn expose_hex<U>(&self, f: impl FnOnce(&str) -> U) -> U {
+                $crate::unstable::with_hex_str(self, f)
+            }
+        }
+    };
+    ($header:tt, serde) => {
+        $crate::private_to_hex_preset!($header, Serialize);
+    };
+    ($header:tt, uppercase) => {};
+    ($header:tt, prefix) => {};
+    ($header:tt, prefix($($arg:tt)*)) => {};
+    ($header:tt, optional_prefix) => {};
+    ($header:tt, lenient $(($($arg:tt)*))?) => {};
+    ($header:tt, strict) => {};
+    ($header:tt, reversed) => {};
+    ($header:tt, constant_time) => {};
+    ($header:tt, zeroize) => {};
+    ($header:tt, checksum) => {};
+    ($header:tt, optional_checksum) => {};
+    ($header:tt, error($($arg:tt)*)) => {};
+    ($header:tt, group($($arg:tt)*)) => {};
+    ({[$($gen:tt)*] $ty:ty [$($where:tt)*]}, Serialize) => {
+        impl<$($gen)*> $crate::private::Serialize for $ty where $($where)* {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
                 S: $crate::private::Serializer,
@@ -78,10 +162,10 @@ macro_rules! private_to_hex_preset {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! private_to_hex_presets {
-    ($ty:ident,) => {};
-    ($ty:ident, $preset:ident, $($presets:ident,)*) => {
-        $crate::private_to_hex_preset!($ty, $preset);
-        $crate::private_to_hex_presets!($ty, $($presets,)*);
+    ($header:tt,) => {};
+    ($header:tt, $preset:ident $(($($arg:tt)*))?, $($presets:tt)*) => {
+        $crate::private_to_hex_preset!($header, $preset $(($($arg)*))?);
+        $crate::private_to_hex_presets!($header, $($presets)*);
     };
 }
 
@@ -90,20 +174,31 @@ macro_rules! private_to_hex_presets {
 /// See [crate-level documentation](index.html) for more information.
 #[macro_export]
 macro_rules! impl_to_hex {
-    ($ty:ident, $len:expr, |$self:ident| $to_hex:expr $(,)?) => {
-        $crate::impl_to_hex!($ty, $len, |$self| $to_hex, [default]);
+    (impl [$($gen:tt)*] $ty:ty $(where [$($where:tt)*])?, $($rest:tt)*) => {
+        $crate::private_impl_to_hex_macro!({[$($gen)*] $ty [$($($where)*)?]}, $($rest)*);
+    };
+    ($ty:ty, $($rest:tt)*) => {
+        $crate::private_impl_to_hex_macro!({[] $ty []}, $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! private_impl_to_hex_macro {
+    ($header:tt, $len:expr, |$self:ident| $to_hex:expr $(,)?) => {
+        $crate::private_impl_to_hex_macro!($header, $len, |$self| $to_hex, [default]);
     };
-    ($ty:ident, $len:expr, |&$self:ident| $to_hex:expr $(,)?) => {
-        $crate::impl_to_hex!($ty, $len, |&$self| $to_hex, [default]);
+    ($header:tt, $len:expr, |&$self:ident| $to_hex:expr $(,)?) => {
+        $crate::private_impl_to_hex_macro!($header, $len, |&$self| $to_hex, [default]);
     };
-    ($ty:ident, $len:expr, |$self:ident| $to_hex:expr, [$($preset:ident),* $(,)?] $(,)?) => {
-        $crate::private_impl_to_hex!($ty, $len, |$self| $to_hex);
-        $crate::private_metadata!($ty, $len, $($preset,)*);
-        $crate::private_to_hex_presets!($ty, $($preset,)*);
+    ($header:tt, $len:expr, |$self:ident| $to_hex:expr, [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?) => {
+        $crate::private_impl_to_hex!($header, $len, |$self| $to_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_metadata!($header, (Some($len), concat!(stringify!($len), " bytes of data")), $($preset $(($($arg)*))?,)*);
+        $crate::private_to_hex_presets!($header, $($preset $(($($arg)*))?,)*);
     };
-    ($ty:ident, $len:expr, |&$self:ident| $to_hex:expr, [$($preset:ident),* $(,)?] $(,)?) => {
-        $crate::private_impl_to_hex!($ty, $len, |&$self| $to_hex);
-        $crate::private_metadata!($ty, $len, $($preset,)*);
-        $crate::private_to_hex_presets!($ty, $($preset,)*);
+    ($header:tt, $len:expr, |&$self:ident| $to_hex:expr, [$($preset:ident $(($($arg:tt)*))?),* $(,)?] $(,)?) => {
+        $crate::private_impl_to_hex!($header, $len, |&$self| $to_hex, [$($preset $(($($arg)*))?,)*]);
+        $crate::private_metadata!($header, (Some($len), concat!(stringify!($len), " bytes of data")), $($preset $(($($arg)*))?,)*);
+        $crate::private_to_hex_presets!($header, $($preset $(($($arg)*))?,)*);
     };
 }
@@ -1,50 +1,756 @@
 #![allow(clippy::module_name_repetitions)]
 
+use super::keccak::Keccak256;
+use super::simd;
+use super::{Checksum, Length, Options, Prefix};
 use crate::FromHexError;
 
-const BYTES: [u8; 16] = *b"0123456789abcdef";
+pub(super) const LOWER: [u8; 16] = *b"0123456789abcdef";
+pub(super) const UPPER: [u8; 16] = *b"0123456789ABCDEF";
 
 /// Encode a binary `value` into the `buffer` as a hexadecimal representation.
 ///
-/// # Panic
+/// # Panics
 /// Panics if `value.len() * 2 != buffer.len()`.
 pub fn encode_hex<'a>(value: &[u8], buffer: &'a mut [u8]) -> &'a str {
+    encode(value, buffer, &LOWER)
+}
+
+/// Encode a binary `value` into the `buffer` as an uppercase hexadecimal representation.
+///
+/// # Panics
+/// Panics if `value.len() * 2 != buffer.len()`.
+pub fn encode_hex_upper<'a>(value: &[u8], buffer: &'a mut [u8]) -> &'a str {
+    encode(value, buffer, &UPPER)
+}
+
+/// Encode a binary `value` into the `buffer` as configured by the `options`.
+///
+/// # Panics
+/// Panics if `value.len() * 2 != buffer.len()`.
+pub fn encode_hex_options<'a>(value: &[u8], buffer: &'a mut [u8], options: &Options) -> &'a str {
+    let checksum = options.checksum != Checksum::Disabled;
+    let uppercase = options.uppercase && !checksum;
+    if options.constant_time {
+        encode_constant_time(value, buffer, uppercase);
+    } else {
+        encode(value, buffer, if uppercase { &UPPER } else { &LOWER });
+    }
+    if options.reversed {
+        // reverse the order of the bytes, but not the order of the digits within each byte
+        buffer.reverse();
+        buffer.chunks_exact_mut(2).for_each(|pair| pair.swap(0, 1));
+    }
+    if checksum {
+        let hash = keccak256(buffer.iter().copied());
+        buffer
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| checksum_uppercase(&hash, *i))
+            .for_each(|(_, c)| c.make_ascii_uppercase());
+    }
+    core::str::from_utf8(buffer).unwrap()
+}
+
+fn encode<'a>(value: &[u8], buffer: &'a mut [u8], table: &[u8; 16]) -> &'a str {
+    assert_eq!(value.len() * 2, buffer.len());
+    let done = simd::encode(value, buffer, table);
+    value[done..]
+        .iter()
+        .copied()
+        .zip(buffer[done * 2..].chunks_mut(2))
+        .for_each(|(a, b)| {
+            b[0] = table[usize::from(a >> 4)];
+            b[1] = table[usize::from(a & 0xf)];
+        });
+    core::str::from_utf8(buffer).unwrap()
+}
+
+/// Encode a binary `value` as lowercase hexadecimal digits in a `const` context.
+///
+/// `M` must be `2 * N`. Use `core::str::from_utf8` to convert the result to a `&str`.
+///
+/// ```
+/// use hexutil::unstable::const_encode;
+///
+/// const DOMAIN: [u8; 8] = const_encode(b"\x01\x23\xab\xcd");
+///
+/// assert_eq!(core::str::from_utf8(&DOMAIN), Ok("0123abcd"));
+/// ```
+///
+/// # Panics
+/// Panics (a compile error in `const` items) if `M != 2 * N`.
+#[must_use]
+pub const fn const_encode<const N: usize, const M: usize>(value: &[u8; N]) -> [u8; M] {
+    assert!(
+        M == N * 2,
+        "the hex buffer must be twice as long as the value"
+    );
+    let mut buffer = [0; M];
+    let mut i = 0;
+    while i < N {
+        buffer[i * 2] = LOWER[(value[i] >> 4) as usize];
+        buffer[i * 2 + 1] = LOWER[(value[i] & 0xf) as usize];
+        i += 1;
+    }
+    buffer
+}
+
+/// Decode a hexadecimal `value` of exactly `2 * N` digits in a `const` context.
+///
+/// Both lowercase and uppercase digits are accepted, a prefix is not allowed.
+///
+/// ```
+/// use hexutil::unstable::const_decode;
+///
+/// struct Hash([u8; 4]);
+///
+/// hexutil::impl_hex!(Hash, 4, |&self| &self.0, |data| Ok(Self(data)));
+///
+/// const GENESIS: Hash = Hash(const_decode("0123abCD"));
+///
+/// assert_eq!(GENESIS.to_string(), "0123abcd");
+/// ```
+///
+/// The options of the type are not known, the bytes are always in the order written. Use
+/// [`const_decode_reversed`] for types with the `reversed` preset.
+///
+/// Invalid values are rejected at compile time:
+///
+/// ```compile_fail
+/// const VALUE: [u8; 2] = hexutil::unstable::const_decode("12g4");
+/// ```
+///
+/// # Panics
+/// Panics (a compile error in `const` items) if the length of `value` is not `2 * N` or
+/// `value` contains an invalid character (the message contains its index).
+#[must_use]
+pub const fn const_decode<const N: usize>(value: &str) -> [u8; N] {
+    let value = value.as_bytes();
+    assert!(value.len() == N * 2, "invalid number of hex digits");
+    let mut buffer = [0; N];
+    let mut i = 0;
+    while i < N {
+        buffer[i] = const_decode_byte(value, i * 2);
+        i += 1;
+    }
+    buffer
+}
+
+/// Decode a hexadecimal `value` of exactly `2 * N` digits in reversed byte order in a `const`
+/// context (for types with the `reversed` preset).
+///
+/// ```
+/// use hexutil::unstable::const_decode_reversed;
+///
+/// struct Txid([u8; 4]);
+///
+/// hexutil::impl_hex!(Txid, 4, |&self| &self.0, |data| Ok(Self(data)), [default, reversed]);
+///
+/// const GENESIS: Txid = Txid(const_decode_reversed("0123abcd"));
+///
+/// assert_eq!(GENESIS.0, [0xcd, 0xab, 0x23, 0x01]);
+/// assert_eq!(GENESIS.to_string(), "0123abcd");
+/// ```
+///
+/// # Panics
+/// Panics like [`const_decode`].
+#[must_use]
+pub const fn const_decode_reversed<const N: usize>(value: &str) -> [u8; N] {
+    let value = value.as_bytes();
+    assert!(value.len() == N * 2, "invalid number of hex digits");
+    let mut buffer = [0; N];
+    let mut i = 0;
+    while i < N {
+        buffer[N - 1 - i] = const_decode_byte(value, i * 2);
+        i += 1;
+    }
+    buffer
+}
+
+/// Decode the two digits at `index` of `value` (panics on invalid characters).
+const fn const_decode_byte(value: &[u8], index: usize) -> u8 {
+    let Some(high) = from_hex(value[index], Letters::Any) else {
+        invalid_character_panic(index);
+    };
+    let Some(low) = from_hex(value[index + 1], Letters::Any) else {
+        invalid_character_panic(index + 1);
+    };
+    (high << 4) | low
+}
+
+/// Panic with a message containing the `index` of the invalid character (formatting
+/// integers is not supported in `const` contexts).
+const fn invalid_character_panic(index: usize) -> ! {
+    const MESSAGE: &[u8] = b"invalid hex character at index ";
+    let mut message = [0; MESSAGE.len() + 20];
+    let mut len = 0;
+    while len < MESSAGE.len() {
+        message[len] = MESSAGE[len];
+        len += 1;
+    }
+    let mut digits = [0; 20];
+    let mut count = 0;
+    let mut rest = index;
+    loop {
+        // always a single digit
+        #[allow(clippy::cast_possible_truncation)]
+        let digit = (rest % 10) as u8;
+        digits[count] = b'0' + digit;
+        count += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    while count > 0 {
+        count -= 1;
+        message[len] = digits[count];
+        len += 1;
+    }
+    match core::str::from_utf8(message.split_at(len).0) {
+        Ok(message) => panic!("{}", message),
+        Err(_) => panic!("invalid hex character"),
+    }
+}
+
+/// Encode a binary `value` into the `buffer` without secret-dependent branches or table
+/// lookups.
+///
+/// # Panics
+/// Panics if `value.len() * 2 != buffer.len()`.
+pub fn encode_hex_constant_time<'a>(value: &[u8], buffer: &'a mut [u8]) -> &'a str {
+    encode_constant_time(value, buffer, false)
+}
+
+fn encode_constant_time<'a>(value: &[u8], buffer: &'a mut [u8], uppercase: bool) -> &'a str {
     assert_eq!(value.len() * 2, buffer.len());
+    // the distance between `'9' + 1` and `'A'` or `'a'`
+    let offset = if uppercase { 7 } else { 39 };
     value
         .iter()
-        .cloned()
-        .zip(buffer.chunks_mut(2))
-        .for_each(write_hex);
+        .copied()
+        .zip(buffer.chunks_exact_mut(2))
+        .for_each(|(a, b)| {
+            b[0] = encode_nibble_constant_time(a >> 4, offset);
+            b[1] = encode_nibble_constant_time(a & 0xf, offset);
+        });
     core::str::from_utf8(buffer).unwrap()
 }
 
-fn write_hex((a, b): (u8, &mut [u8])) {
-    b[0] = BYTES[usize::from(a >> 4)];
-    b[1] = BYTES[usize::from(a & 0xf)];
+const fn encode_nibble_constant_time(nibble: u8, offset: u8) -> u8 {
+    // `9 - nibble` wraps around (setting the highest bit) if `nibble > 9`
+    let letter = 0u8.wrapping_sub(9u8.wrapping_sub(nibble) >> 7);
+    b'0' + nibble + (letter & offset)
 }
 
 /// Decode a hexadecimal `value` into a binary `buffer`.
-pub fn decode_hex<'a>(value: &[u8], buffer: &'a mut [u8]) -> Result<(), FromHexError> {
+pub fn decode_hex(value: &[u8], buffer: &mut [u8]) -> Result<(), FromHexError> {
+    decode(value, buffer, 0, Letters::Any).map_err(|err| locate(value, err))
+}
+
+/// Decode a hexadecimal `value` into a binary `buffer` without secret-dependent branches or
+/// table lookups.
+///
+/// The position of an invalid character is only searched for after the whole `value` has been
+/// processed.
+pub fn decode_hex_constant_time(value: &[u8], buffer: &mut [u8]) -> Result<(), FromHexError> {
+    decode_constant_time(value, buffer, 0, Letters::Any).map_err(|err| locate(value, err))
+}
+
+/// Decode a hexadecimal `value` into a binary `buffer` ignoring whitespace and `separators`.
+///
+/// The positions of invalid characters are relative to the start of `value`.
+pub fn decode_hex_lenient(
+    value: &[u8],
+    buffer: &mut [u8],
+    separators: &[u8],
+) -> Result<(), FromHexError> {
+    decode_lenient(value, buffer, 0, separators, Letters::Any).map_err(|err| locate(value, err))
+}
+
+/// Decode a hexadecimal `value` into a binary `buffer` as configured by the `options`.
+///
+/// The positions of invalid characters are relative to the start of `value`.
+pub fn decode_hex_options(
+    value: &[u8],
+    buffer: &mut [u8],
+    options: &Options,
+) -> Result<(), FromHexError> {
+    decode_hex_separators(value, buffer, options, options.separators)
+}
+
+/// Decode a hexadecimal `value` like [`decode_hex_options`], but ignore `separators` instead of
+/// `options.separators` in lenient mode.
+pub fn decode_hex_separators(
+    value: &[u8],
+    buffer: &mut [u8],
+    options: &Options,
+    separators: &[u8],
+) -> Result<(), FromHexError> {
+    let offset = prefix_len(value, options)?;
+    let letters = Letters::new(options);
+    let lenient = is_lenient(options);
+    if lenient {
+        decode_lenient(value, buffer, offset, separators, letters)
+    } else if options.constant_time {
+        decode_constant_time(&value[offset..], buffer, offset, letters)
+    } else {
+        decode(&value[offset..], buffer, offset, letters)
+    }
+    .map_err(|err| locate(value, err))?;
+    if options.checksum != Checksum::Disabled {
+        let digits = value
+            .iter()
+            .copied()
+            .skip(offset)
+            .filter(|c| !(lenient && is_separator(*c, separators)));
+        verify_checksum(digits, options.checksum)?;
+    }
+    if options.reversed {
+        buffer.reverse();
+    }
+    Ok(())
+}
+
+/// Compare a binary `value` with the hexadecimal representation `hex` without secret-dependent
+/// branches or table lookups (returns `1` if equal and `0` otherwise).
+///
+/// Only the length and prefix of `hex` are checked in variable time. Separators (`lenient`)
+/// are not accepted and a `checksum` is not verified.
+#[cfg(feature = "subtle")]
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn eq_hex_constant_time(value: &[u8], hex: &[u8], options: &Options) -> u8 {
+    let Ok(offset) = prefix_len(hex, options) else {
+        return 0;
+    };
+    let digits = &hex[offset..];
+    if digits.len() != value.len() * 2 {
+        return 0;
+    }
+    let letters = Letters::new(options);
+    let mut equal = -1;
+    for (i, c) in digits.chunks_exact(2).enumerate() {
+        let b = if options.reversed {
+            value[value.len() - 1 - i]
+        } else {
+            value[i]
+        };
+        let (high, high_valid) = decode_nibble_constant_time(c[0], letters);
+        let (low, low_valid) = decode_nibble_constant_time(c[1], letters);
+        // `difference - 1` is negative if the difference is zero
+        let same = (i16::from(((high << 4) | low) ^ b) - 1) >> 8;
+        equal &= high_valid & low_valid & same;
+    }
+    (equal & 1) as u8
+}
+
+/// Count the hexadecimal digits in `value` as configured by the `options`.
+///
+/// This excludes the prefix and (in lenient mode) whitespace and separators.
+pub fn count_hex_digits(value: &[u8], options: &Options) -> Result<usize, FromHexError> {
+    count_hex_digits_separators(value, options, options.separators)
+}
+
+/// Count the hexadecimal digits in `value` like [`count_hex_digits`], but ignore `separators`
+/// instead of `options.separators` in lenient mode.
+pub fn count_hex_digits_separators(
+    value: &[u8],
+    options: &Options,
+    separators: &[u8],
+) -> Result<usize, FromHexError> {
+    let offset = prefix_len(value, options)?;
+    if is_lenient(options) {
+        Ok(count_lenient(&value[offset..], separators))
+    } else {
+        Ok(value.len() - offset)
+    }
+}
+
+/// Check if whitespace and separators are ignored (never in strict or constant-time mode).
+const fn is_lenient(options: &Options) -> bool {
+    options.lenient && !options.strict && !options.constant_time
+}
+
+/// Return the length of the `0x` prefix of `value`.
+fn prefix_len(value: &[u8], options: &Options) -> Result<usize, FromHexError> {
+    find_prefix(value, options).ok_or(FromHexError::MissingPrefix)
+}
+
+/// Return the length of the `0x` prefix of `value` (`None` if a required prefix is missing).
+///
+/// In strict mode only the canonical form is accepted: a lowercase `0x` prefix if (and only if)
+/// it is added to the hexadecimal representation.
+const fn find_prefix(value: &[u8], options: &Options) -> Option<usize> {
+    let lower = value.len() >= 2 && value[0] == b'0' && value[1] == b'x';
+    let upper = value.len() >= 2 && value[0] == b'0' && value[1] == b'X';
+    let (found, prefix) = if options.strict {
+        let prefix = if options.emit_prefix {
+            Prefix::Required
+        } else {
+            Prefix::Forbidden
+        };
+        (lower, prefix)
+    } else {
+        (lower || upper, options.prefix)
+    };
+    match prefix {
+        Prefix::Optional | Prefix::Required if found => Some(2),
+        Prefix::Forbidden | Prefix::Optional => Some(0),
+        Prefix::Required => None,
+    }
+}
+
+/// The reason a `hex!(Type, "...")` literal is rejected by [`check_hex_literal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralError {
+    /// The required `0x` prefix is missing (or not lowercase in strict mode).
+    MissingPrefix,
+
+    /// A `0x` prefix is not accepted.
+    UnexpectedPrefix,
+
+    /// An uppercase digit is not in the canonical case (`strict` preset).
+    Uppercase,
+
+    /// A lowercase digit is not in the canonical case (`strict` and `uppercase` presets).
+    Lowercase,
+}
+
+/// Check the prefix and the case of the digits of a `hex!(Type, "...")` literal (without
+/// whitespace) in a `const` context.
+///
+/// The `hex!` macro reports the errors at the position of the offending character. The
+/// `checksum` is verified by [`verify_literal_checksum`] when the value is created.
+pub const fn check_hex_literal(value: &str, options: &Options) -> Result<(), LiteralError> {
+    let value = value.as_bytes();
+    let Some(offset) = find_prefix(value, options) else {
+        return Err(LiteralError::MissingPrefix);
+    };
+    let prefixed = value.len() >= 2 && value[0] == b'0' && matches!(value[1], b'x' | b'X');
+    if offset == 0 && prefixed {
+        return Err(LiteralError::UnexpectedPrefix);
+    }
+    let letters = Letters::new(options);
+    let mut i = offset;
+    while i < value.len() {
+        if from_hex(value[i], letters).is_none() {
+            return Err(if value[i].is_ascii_uppercase() {
+                LiteralError::Uppercase
+            } else {
+                LiteralError::Lowercase
+            });
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Verify the `checksum` of a `hex!(Type, "...")` literal (without whitespace) if enabled.
+pub fn verify_literal_checksum(value: &str, options: &Options) -> Result<(), FromHexError> {
+    if options.checksum == Checksum::Disabled {
+        return Ok(());
+    }
+    let offset = prefix_len(value.as_bytes(), options)?;
+    verify_checksum(value.bytes().skip(offset), options.checksum)
+}
+
+/// The case of the letters accepted while parsing.
+#[derive(Clone, Copy)]
+enum Letters {
+    Any,
+    Lower,
+    Upper,
+}
+
+impl Letters {
+    const fn new(options: &Options) -> Self {
+        if !options.strict || !matches!(options.checksum, Checksum::Disabled) {
+            Self::Any
+        } else if options.uppercase {
+            Self::Upper
+        } else {
+            Self::Lower
+        }
+    }
+
+    /// The masks of the accepted uppercase and lowercase letters (all ones if accepted).
+    const fn masks(self) -> (i16, i16) {
+        match self {
+            Self::Any => (-1, -1),
+            Self::Lower => (0, -1),
+            Self::Upper => (-1, 0),
+        }
+    }
+
+    /// The lookup table converting characters to nibbles.
+    const fn table(self) -> &'static [u8; 256] {
+        match self {
+            Self::Any => &DECODE_ANY,
+            Self::Lower => &DECODE_LOWER,
+            Self::Upper => &DECODE_UPPER,
+        }
+    }
+}
+
+/// The value of characters that are not accepted in the decoding tables.
+const INVALID: u8 = 0xff;
+
+const DECODE_ANY: [u8; 256] = decode_table(Letters::Any);
+const DECODE_LOWER: [u8; 256] = decode_table(Letters::Lower);
+const DECODE_UPPER: [u8; 256] = decode_table(Letters::Upper);
+
+/// Create a table mapping each character to its nibble (or `INVALID`).
+const fn decode_table(letters: Letters) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut c = 0;
+    loop {
+        if let Some(nibble) = from_hex(c, letters) {
+            table[c as usize] = nibble;
+        }
+        if c == u8::MAX {
+            return table;
+        }
+        c += 1;
+    }
+}
+
+fn decode(
+    value: &[u8],
+    buffer: &mut [u8],
+    offset: usize,
+    letters: Letters,
+) -> Result<(), FromHexError> {
     if value.len() != buffer.len() * 2 {
-        return Err(FromHexError::InvalidLength(value.len()));
+        return Err(invalid_length(buffer, value.len()));
+    }
+    let done = match letters {
+        Letters::Any => simd::decode(value, buffer),
+        Letters::Lower | Letters::Upper => 0,
+    };
+    let value = &value[done * 2..];
+    let table = letters.table();
+    // decode everything and check for invalid characters once at the end
+    let mut invalid = 0;
+    for (c, b) in value.chunks_exact(2).zip(&mut buffer[done..]) {
+        let high = table[usize::from(c[0])];
+        let low = table[usize::from(c[1])];
+        invalid |= high | low;
+        *b = (high << 4) | low;
+    }
+    if invalid == INVALID {
+        return first_invalid_character(value, offset + done * 2, letters);
     }
+    Ok(())
+}
+
+fn decode_constant_time(
+    value: &[u8],
+    buffer: &mut [u8],
+    offset: usize,
+    letters: Letters,
+) -> Result<(), FromHexError> {
+    if value.len() != buffer.len() * 2 {
+        return Err(invalid_length(buffer, value.len()));
+    }
+    let mut valid = -1;
+    for (c, b) in value.chunks_exact(2).zip(buffer.iter_mut()) {
+        let (high, high_valid) = decode_nibble_constant_time(c[0], letters);
+        let (low, low_valid) = decode_nibble_constant_time(c[1], letters);
+        valid &= high_valid & low_valid;
+        *b = (high << 4) | low;
+    }
+    if valid == 0 {
+        return first_invalid_character(value, offset, letters);
+    }
+    Ok(())
+}
+
+/// Convert a character to a nibble using only arithmetic.
+///
+/// Returns the nibble and a mask which is all ones if the character is valid.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn decode_nibble_constant_time(c: u8, letters: Letters) -> (u8, i16) {
+    let c = c as i16;
+    // `(low - 1 - c) & (c - high - 1)` is negative if `low <= c <= high`
+    let digit = ((0x2f - c) & (c - 0x3a)) >> 8;
+    let upper = ((0x40 - c) & (c - 0x47)) >> 8;
+    let lower = ((0x60 - c) & (c - 0x67)) >> 8;
+    let (upper_mask, lower_mask) = letters.masks();
+    let upper = upper & upper_mask;
+    let lower = lower & lower_mask;
+    let nibble = (digit & (c - 0x30)) | (upper & (c - 0x37)) | (lower & (c - 0x57));
+    (nibble as u8, digit | upper | lower)
+}
+
+/// Return the error for the first character of `value` not accepted by `letters`.
+fn first_invalid_character(
+    value: &[u8],
+    offset: usize,
+    letters: Letters,
+) -> Result<(), FromHexError> {
+    let table = letters.table();
     value
-        .chunks(2)
+        .iter()
+        .position(|&c| table[usize::from(c)] == INVALID)
+        .map_or(Ok(()), |i| Err(invalid_character(offset + i, value[i])))
+}
+
+/// Verify the EIP-55 mixed-case checksum of the hexadecimal `digits`.
+fn verify_checksum<I>(digits: I, checksum: Checksum) -> Result<(), FromHexError>
+where
+    I: Iterator<Item = u8> + Clone,
+{
+    let mut letters = digits.clone().filter(u8::is_ascii_alphabetic);
+    if checksum == Checksum::Optional
+        && (letters.clone().all(|c| c.is_ascii_lowercase())
+            || letters.all(|c| c.is_ascii_uppercase()))
+    {
+        return Ok(());
+    }
+    let hash = keccak256(digits.clone().map(|c| c.to_ascii_lowercase()));
+    let valid = digits
         .enumerate()
-        .zip(buffer.iter_mut())
-        .try_for_each(read_hex)
+        .filter(|(_, c)| c.is_ascii_alphabetic())
+        .all(|(i, c)| c.is_ascii_uppercase() == checksum_uppercase(&hash, i));
+    if valid {
+        Ok(())
+    } else {
+        Err(FromHexError::InvalidChecksum)
+    }
+}
+
+fn keccak256(digits: impl Iterator<Item = u8>) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    digits.for_each(|c| hasher.update(&[c]));
+    hasher.finalize()
+}
+
+/// Return whether the `i`-th digit is uppercase in the checksummed representation.
+///
+/// Digits are uppercase if the corresponding nibble of the hash is at least 8. The hash is
+/// repeated for representations longer than 64 digits.
+const fn checksum_uppercase(hash: &[u8; 32], i: usize) -> bool {
+    let b = hash[(i / 2) % hash.len()];
+    let nibble = if i.is_multiple_of(2) { b >> 4 } else { b & 0xf };
+    nibble >= 8
 }
 
-fn read_hex(((i, c), b): ((usize, &[u8]), &mut u8)) -> Result<(), FromHexError> {
-    *b = (from_hex(i * 2, c[0])? << 4) | from_hex(i * 2 + 1, c[1])?;
+const fn invalid_length(buffer: &[u8], digits: usize) -> FromHexError {
+    FromHexError::InvalidLength {
+        name: "buffer",
+        expected: Length::exact(buffer.len()),
+        actual: digits,
+    }
+}
+
+/// Replace the character and index of an invalid character error using the original `value`.
+///
+/// While decoding only the byte offset is known. The index is counted in characters and the
+/// character is decoded as UTF-8.
+fn locate(value: &[u8], err: FromHexError) -> FromHexError {
+    match err {
+        FromHexError::InvalidHexCharacter { offset, .. } => {
+            let (character, index) = char_at(value, offset);
+            FromHexError::InvalidHexCharacter {
+                character,
+                index,
+                offset,
+            }
+        }
+        FromHexError::InvalidCase { offset, .. } => {
+            let (character, index) = char_at(value, offset);
+            FromHexError::InvalidCase {
+                character,
+                index,
+                offset,
+            }
+        }
+        err => err,
+    }
+}
+
+fn char_at(value: &[u8], offset: usize) -> (char, usize) {
+    let index = value[..offset]
+        .iter()
+        .filter(|&&b| b & 0xc0 != 0x80)
+        .count();
+    let rest = &value[offset..];
+    let rest = &rest[..rest.len().min(4)];
+    let valid = core::str::from_utf8(rest)
+        .unwrap_or_else(|err| core::str::from_utf8(&rest[..err.valid_up_to()]).unwrap_or_default());
+    let character = valid.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER);
+    (character, index)
+}
+
+fn count_lenient(value: &[u8], separators: &[u8]) -> usize {
+    value
+        .iter()
+        .filter(|c| !is_separator(**c, separators))
+        .count()
+}
+
+fn is_separator(c: u8, separators: &[u8]) -> bool {
+    c.is_ascii_whitespace() || separators.contains(&c)
+}
+
+fn decode_lenient(
+    value: &[u8],
+    buffer: &mut [u8],
+    offset: usize,
+    separators: &[u8],
+    letters: Letters,
+) -> Result<(), FromHexError> {
+    let digits = count_lenient(&value[offset..], separators);
+    if digits != buffer.len() * 2 {
+        return Err(invalid_length(buffer, digits));
+    }
+    let table = letters.table();
+    let mut buffer = buffer.iter_mut();
+    let mut high = None;
+    for (i, &c) in value.iter().enumerate().skip(offset) {
+        if is_separator(c, separators) {
+            continue;
+        }
+        let nibble = match table[usize::from(c)] {
+            INVALID => return Err(invalid_character(i, c)),
+            nibble => nibble,
+        };
+        match high.take() {
+            None => high = Some(nibble),
+            Some(high) => {
+                if let Some(b) = buffer.next() {
+                    *b = (high << 4) | nibble;
+                }
+            }
+        }
+    }
     Ok(())
 }
 
-fn from_hex(i: usize, c: u8) -> Result<u8, FromHexError> {
+/// Return the error for the invalid character `c` at offset `i`.
+const fn invalid_character(i: usize, c: u8) -> FromHexError {
+    if c.is_ascii_hexdigit() {
+        FromHexError::InvalidCase {
+            character: c as char,
+            index: i,
+            offset: i,
+        }
+    } else {
+        FromHexError::InvalidHexCharacter {
+            character: c as char,
+            index: i,
+            offset: i,
+        }
+    }
+}
+
+const fn from_hex(c: u8, letters: Letters) -> Option<u8> {
     match c {
-        b'A'..=b'F' => Ok(c - b'A' + 10),
-        b'a'..=b'f' => Ok(c - b'a' + 10),
-        b'0'..=b'9' => Ok(c - b'0'),
-        _ => Err(FromHexError::InvalidHexCharacter(i, c)),
+        b'A'..=b'F' if !matches!(letters, Letters::Lower) => Some(c - b'A' + 10),
+        b'a'..=b'f' if !matches!(letters, Letters::Upper) => Some(c - b'a' + 10),
+        b'0'..=b'9' => Some(c - b'0'),
+        _ => None,
     }
 }
@@ -0,0 +1,117 @@
+//! A minimal Keccak-256 implementation (as used by Ethereum) for EIP-55 checksums.
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PERMUTATION: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// An incremental Keccak-256 hasher.
+pub struct Keccak256 {
+    state: [u64; 25],
+    pos: usize,
+}
+
+impl Keccak256 {
+    pub const fn new() -> Self {
+        Self {
+            state: [0; 25],
+            pos: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.xor_byte(self.pos, b);
+            self.pos += 1;
+            if self.pos == RATE {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        self.xor_byte(self.pos, 0x01);
+        self.xor_byte(RATE - 1, 0x80);
+        keccak_f(&mut self.state);
+        let mut hash = [0; 32];
+        hash.chunks_exact_mut(8)
+            .zip(&self.state)
+            .for_each(|(chunk, lane)| chunk.copy_from_slice(&lane.to_le_bytes()));
+        hash
+    }
+
+    fn xor_byte(&mut self, pos: usize, b: u8) {
+        self.state[pos / 8] ^= u64::from(b) << (8 * (pos % 8));
+    }
+}
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for &round_constant in &ROUND_CONSTANTS {
+        // theta
+        let mut columns = [0; 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let t = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            state
+                .iter_mut()
+                .skip(x)
+                .step_by(5)
+                .for_each(|lane| *lane ^= t);
+        }
+
+        // rho and pi
+        let mut last = state[1];
+        for (&i, &rotation) in PERMUTATION.iter().zip(&ROTATIONS) {
+            let lane = state[i];
+            state[i] = last.rotate_left(rotation);
+            last = lane;
+        }
+
+        // chi
+        for row in state.chunks_exact_mut(5) {
+            let mut copy = [0; 5];
+            copy.copy_from_slice(row);
+            for (x, lane) in row.iter_mut().enumerate() {
+                *lane ^= !copy[(x + 1) % 5] & copy[(x + 2) % 5];
+            }
+        }
+
+        // iota
+        state[0] ^= round_constant;
+    }
+}
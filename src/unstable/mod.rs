@@ -3,11 +3,23 @@
 //! All traits and functions in this module are unstable. They could change in the future.
 
 mod hex;
+mod keccak;
+mod options;
 pub mod serde;
+mod simd;
 
-pub use hex::{decode_hex, encode_hex};
+#[cfg(feature = "subtle")]
+pub use hex::eq_hex_constant_time;
+pub use hex::{
+    check_hex_literal, const_decode, const_decode_reversed, const_encode, count_hex_digits,
+    count_hex_digits_separators, decode_hex, decode_hex_constant_time, decode_hex_lenient,
+    decode_hex_options, decode_hex_separators, encode_hex, encode_hex_constant_time,
+    encode_hex_options, encode_hex_upper, verify_literal_checksum, LiteralError,
+};
+pub use options::{Checksum, Options, Prefix};
 
-use crate::{FromHexError, ToHex};
+use crate::{FromHex, FromHexError, ToHex};
+use core::fmt;
 
 /// Convert a type to a binary or hexadecimal representation.
 pub trait ToHexCore {
@@ -17,6 +29,9 @@ pub trait ToHexCore {
     /// The buffer for the hexadecimal representation.
     type Buffer;
 
+    /// The options used to create the hexadecimal representation.
+    const OPTIONS: Options = Options::DEFAULT;
+
     /// Create a new empty buffer of size `len`.
     fn create_buffer(len: usize) -> Self::Buffer;
 
@@ -26,6 +41,11 @@ pub trait ToHexCore {
     /// Create a bytes type.
     fn to_binary_bytes(&self) -> Self::Bytes;
 
+    /// Return a mutable reference to the bytes in `bytes` (used to wipe them with `zeroize`).
+    ///
+    /// This is empty if `bytes` does not contain a copy of the binary representation.
+    fn binary_bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8];
+
     /// Return a reference to the binary representation.
     fn as_binary_bytes<'a>(&'a self, bytes: &'a Self::Bytes) -> &'a [u8];
 }
@@ -35,6 +55,23 @@ pub trait FromHexCore: Sized {
     /// The type of the binary representation.
     type Bytes;
 
+    /// The error returned while creating this type (`error` preset, defaults to `FromHexError`).
+    ///
+    /// Errors in the hexadecimal representation are converted using the `From` implementation.
+    type Error: From<FromHexError>;
+
+    /// The allowed number of bytes.
+    ///
+    /// This is checked before `create_bytes` is called.
+    const LENGTH: Length = Length::ANY;
+
+    /// The options used to parse the hexadecimal representation.
+    const OPTIONS: Options = Options::DEFAULT;
+
+    /// The name of this type used in error messages (the same as `Metadata::NAME` if both
+    /// are implemented by the macros).
+    const TYPE_NAME: &'static str;
+
     /// Create a bytes type of size `len`.
     fn create_bytes(len: Option<usize>) -> Self::Bytes;
 
@@ -42,17 +79,606 @@ pub trait FromHexCore: Sized {
     fn bytes_as_mut(bytes: &mut Self::Bytes) -> &mut [u8];
 
     /// Create an instance of this type from `bytes`.
-    fn from_binary_bytes(bytes: Self::Bytes) -> Result<Self, FromHexError>;
+    ///
+    /// This function owns `bytes`, also if it returns an error. It has to wipe them itself if
+    /// the `zeroize` preset is used.
+    fn from_binary_bytes(bytes: Self::Bytes) -> Result<Self, Self::Error>;
+}
+
+/// Create a value from its hexadecimal representation using custom `options`.
+///
+/// This is used to implement [`FromHex::from_hex`].
+pub fn from_hex_options<T>(buf: &[u8], options: &Options) -> Result<T, T::Error>
+where
+    T: FromHex,
+{
+    from_hex_separators(buf, options, options.separators)
+}
+
+/// Create a value like [`from_hex_options`], but ignore `separators` instead of
+/// `options.separators` in lenient mode.
+///
+/// This is used to implement [`ParseHex::parse_hex_lenient`](crate::ParseHex::parse_hex_lenient).
+pub fn from_hex_separators<T>(
+    buf: &[u8],
+    options: &Options,
+    separators: &[u8],
+) -> Result<T, T::Error>
+where
+    T: FromHex,
+{
+    let bytes = bytes_from_hex_options::<T>(buf, options, separators)?;
+    T::from_binary_bytes(bytes)
+}
+
+/// Compare two values in constant time (`ConstantTimeEq` and `PartialEq` presets).
+///
+/// The binary representations are compared, values of different lengths are never equal.
+#[cfg(feature = "subtle")]
+pub fn ct_eq<T>(a: &T, b: &T) -> subtle::Choice
+where
+    T: ?Sized + ToHex,
+{
+    use subtle::ConstantTimeEq;
+
+    let mut a_bytes = a.to_binary_bytes();
+    let mut b_bytes = b.to_binary_bytes();
+    let equal = a
+        .as_binary_bytes(&a_bytes)
+        .ct_eq(b.as_binary_bytes(&b_bytes));
+    zeroize(&T::OPTIONS, T::binary_bytes_as_mut(&mut a_bytes));
+    zeroize(&T::OPTIONS, T::binary_bytes_as_mut(&mut b_bytes));
+    equal
+}
+
+/// Compare a `value` with an untrusted hexadecimal representation in constant time.
+///
+/// The `hex` input is never decoded, see [`eq_hex_constant_time`] for details.
+#[cfg(feature = "subtle")]
+pub fn ct_eq_hex<T>(value: &T, hex: &[u8]) -> subtle::Choice
+where
+    T: ?Sized + ToHex,
+{
+    let mut bytes = value.to_binary_bytes();
+    let equal = eq_hex_constant_time(value.as_binary_bytes(&bytes), hex, &T::OPTIONS);
+    zeroize(&T::OPTIONS, T::binary_bytes_as_mut(&mut bytes));
+    equal.into()
+}
+
+/// Create a value from the decoded `bytes` of a `hex!` `literal` (in the order written).
+///
+/// The `literal` (without whitespace) is used to verify the checksum. The prefix and the case
+/// of the digits are checked at compile time by [`check_hex_literal`].
+///
+/// # Panics
+/// Panics if the number of bytes or the checksum is invalid or `T::from_binary_bytes` returns
+/// an error.
+#[must_use]
+pub fn from_hex_literal<T>(literal: &str, bytes: &[u8]) -> T
+where
+    T: FromHex,
+{
+    if let Err(err) = verify_literal_checksum(literal, &T::OPTIONS) {
+        panic!("invalid {} literal: {}", T::TYPE_NAME, err);
+    }
+    let mut value = T::create_bytes(Some(bytes.len()));
+    {
+        let value = T::bytes_as_mut(&mut value);
+        assert_eq!(
+            value.len(),
+            bytes.len(),
+            "invalid length of {}",
+            T::TYPE_NAME
+        );
+        value.copy_from_slice(bytes);
+        if T::OPTIONS.reversed {
+            value.reverse();
+        }
+    }
+    T::from_binary_bytes(value).unwrap_or_else(|_| panic!("invalid {} literal", T::TYPE_NAME))
+}
+
+/// Overwrite `bytes` with zeros if the `zeroize` preset is used.
+#[cfg(feature = "zeroize")]
+fn zeroize(options: &Options, bytes: &mut [u8]) {
+    if options.zeroize {
+        zeroize::Zeroize::zeroize(bytes);
+    }
+}
+
+/// Overwrite `bytes` with zeros if the `zeroize` preset is used.
+#[cfg(not(feature = "zeroize"))]
+const fn zeroize(_options: &Options, _bytes: &mut [u8]) {}
+
+/// Decode the binary representation of `T` without creating an instance of `T`.
+///
+/// The bytes are wiped on errors if the `zeroize` preset is used.
+fn bytes_from_hex_options<T>(
+    buf: &[u8],
+    options: &Options,
+    separators: &[u8],
+) -> Result<T::Bytes, FromHexError>
+where
+    T: FromHex,
+{
+    let digits = count_hex_digits_separators(buf, options, separators)?;
+    let invalid_length = || FromHexError::InvalidLength {
+        name: T::TYPE_NAME,
+        expected: T::LENGTH,
+        actual: digits,
+    };
+    if digits & 1 != 0 || !T::LENGTH.contains(digits / 2) {
+        return Err(invalid_length());
+    }
+    let mut bytes = T::create_bytes(Some(digits / 2));
+    {
+        let bytes = T::bytes_as_mut(&mut bytes);
+        if bytes.len() * 2 != digits {
+            return Err(invalid_length());
+        }
+        if let Err(err) = decode_hex_separators(buf, bytes, options, separators) {
+            zeroize(&T::OPTIONS, bytes);
+            return Err(err);
+        }
+    }
+    Ok(bytes)
+}
+
+/// The allowed number of bytes of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Length {
+    /// The minimum number of bytes.
+    pub min: usize,
+
+    /// The maximum number of bytes (`None` if there is no upper bound).
+    pub max: Option<usize>,
+
+    /// The number of bytes must be a multiple of this value (must not be zero).
+    pub multiple_of: usize,
+}
+
+impl Length {
+    /// Any number of bytes.
+    pub const ANY: Self = Self {
+        min: 0,
+        max: None,
+        multiple_of: 1,
+    };
+
+    /// Exactly `len` bytes.
+    #[must_use]
+    pub const fn exact(len: usize) -> Self {
+        Self {
+            min: len,
+            max: Some(len),
+            multiple_of: 1,
+        }
+    }
+
+    /// Check if `len` bytes are allowed.
+    #[must_use]
+    pub const fn contains(&self, len: usize) -> bool {
+        len >= self.min
+            && match self.max {
+                Some(max) => len <= max,
+                None => true,
+            }
+            && len.is_multiple_of(self.multiple_of)
+    }
+
+    /// The allowed number of hexadecimal digits (if `self` is the allowed number of bytes).
+    #[must_use]
+    pub const fn digits(&self) -> Self {
+        Self {
+            min: self.min * 2,
+            max: match self.max {
+                Some(max) => Some(max * 2),
+                None => None,
+            },
+            multiple_of: self.multiple_of * 2,
+        }
+    }
+
+    fn fmt_unit(&self, f: &mut fmt::Formatter, unit: &str) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => return write!(f, "{min} {unit}"),
+            (0, None) => write!(f, "any number of {unit}")?,
+            (min, None) => write!(f, "at least {min} {unit}")?,
+            (0, Some(max)) => write!(f, "at most {max} {unit}")?,
+            (min, Some(max)) => write!(f, "{min} to {max} {unit}")?,
+        }
+        if self.multiple_of > 1 {
+            write!(f, ", a multiple of {}", self.multiple_of)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the number of bytes and hexadecimal digits, e.g. `2 bytes (4 hex digits)`.
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_unit(f, "bytes")?;
+        f.write_str(" (")?;
+        self.digits().fmt_unit(f, "hex digits")?;
+        f.write_str(")")
+    }
 }
 
 /// Get a reference to the hexadecimal representation of a `value`.
+///
+/// The temporary buffers are wiped afterwards if the `zeroize` preset is used.
 pub fn with_hex_str<T, U>(value: &T, f: impl FnOnce(&str) -> U) -> U
 where
     T: ?Sized + ToHex,
 {
-    let bytes = value.to_binary_bytes();
-    let bytes = value.as_binary_bytes(&bytes);
-    let mut buffer = T::create_buffer(bytes.len() * 2);
-    let buffer = T::buffer_as_bytes(&mut buffer);
-    f(encode_hex(bytes, buffer))
+    with_hex_str_options(value, &T::OPTIONS, f)
+}
+
+/// Get a reference to the hexadecimal representation of a `value` using custom `options`.
+pub fn with_hex_str_options<T, U>(value: &T, options: &Options, f: impl FnOnce(&str) -> U) -> U
+where
+    T: ?Sized + ToHex,
+{
+    let mut bytes = value.to_binary_bytes();
+    let len = value.as_binary_bytes(&bytes).len();
+    let mut buffer = T::create_buffer(len * 2);
+    let result = f(encode_hex_options(
+        value.as_binary_bytes(&bytes),
+        T::buffer_as_bytes(&mut buffer),
+        options,
+    ));
+    zeroize(&T::OPTIONS, T::buffer_as_bytes(&mut buffer));
+    zeroize(&T::OPTIONS, T::binary_bytes_as_mut(&mut bytes));
+    result
+}
+
+/// Format a `value` using its default hexadecimal representation (`{}`).
+///
+/// Width, fill and alignment are honoured (left-aligned by default). The precision
+/// truncates the output to the given number of hexadecimal digits (`{:.8}`).
+pub fn fmt_display<T>(value: &T, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    fmt_display_options(value, &T::OPTIONS, f)
+}
+
+/// Format a `value` like [`fmt_display`] using custom `options`.
+///
+/// The digits are split into groups of `options.group` bytes separated by `options.separator`.
+pub fn fmt_display_options<T>(value: &T, options: &Options, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    with_hex_str_options(value, options, |s| {
+        let digits = Grouped {
+            hex: s,
+            group: options.group * 2,
+            separator: options.separator,
+        };
+        pad(
+            f,
+            options.prefix_str(),
+            digits,
+            "",
+            fmt::Alignment::Left,
+            false,
+        )
+    })
+}
+
+/// Format a `value` using lowercase hexadecimal digits (`{:x}`).
+///
+/// The alternate flag (`{:#x}`) adds a `0x` prefix. Width, fill, alignment (right-aligned by
+/// default) and zero-padding (`{:08x}`) are honoured. The precision truncates the output to
+/// the given number of hexadecimal digits.
+pub fn fmt_lower_hex<T>(value: &T, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    let mut options = T::OPTIONS;
+    options.uppercase = false;
+    options.checksum = Checksum::Disabled;
+    let prefix = if f.alternate() { "0x" } else { "" };
+    with_hex_str_options(value, &options, |s| {
+        pad(f, prefix, Grouped::new(s), "", fmt::Alignment::Right, true)
+    })
+}
+
+/// Format a `value` using uppercase hexadecimal digits (`{:X}`).
+///
+/// See [`fmt_lower_hex`] for the supported formatting flags.
+pub fn fmt_upper_hex<T>(value: &T, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    let mut options = T::OPTIONS;
+    options.uppercase = true;
+    options.checksum = Checksum::Disabled;
+    let prefix = if f.alternate() { "0x" } else { "" };
+    with_hex_str_options(value, &options, |s| {
+        pad(f, prefix, Grouped::new(s), "", fmt::Alignment::Right, true)
+    })
+}
+
+/// Format a `value` as a tuple struct named `name` containing the hexadecimal representation.
+///
+/// The precision truncates the hexadecimal representation (`{:.8?}`).
+pub fn fmt_debug<T>(value: &T, name: &str, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    with_hex_str(value, |s| {
+        let prefix = T::OPTIONS.prefix_str();
+        let s = truncate(s, f.precision());
+        f.debug_tuple(name)
+            .field(&format_args!("{prefix}{s}"))
+            .finish()
+    })
+}
+
+/// Format a `value` without revealing it (`redacted` preset).
+///
+/// At most the first `visible` hexadecimal digits are shown, e.g. `a1b2…[redacted]`, but never
+/// more than half of them. Width, fill and alignment are honoured (left-aligned by default).
+pub fn fmt_redacted<T>(value: &T, visible: usize, f: &mut fmt::Formatter) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    fmt::Display::fmt(&Redacted { value, visible }, f)
+}
+
+/// Format a `value` as a tuple struct named `name` containing the redacted representation.
+pub fn fmt_debug_redacted<T>(
+    value: &T,
+    name: &str,
+    visible: usize,
+    f: &mut fmt::Formatter,
+) -> fmt::Result
+where
+    T: ?Sized + ToHex,
+{
+    f.debug_tuple(name)
+        .field(&format_args!("{}", Redacted { value, visible }))
+        .finish()
+}
+
+/// The redacted representation of a value.
+struct Redacted<'a, T: ?Sized> {
+    value: &'a T,
+
+    /// The maximum number of hexadecimal digits shown (at most half of them).
+    visible: usize,
+}
+
+impl<T> fmt::Display for Redacted<'_, T>
+where
+    T: ?Sized + ToHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.visible == 0 {
+            return pad(
+                f,
+                "",
+                Grouped::new(""),
+                "[redacted]",
+                fmt::Alignment::Left,
+                false,
+            );
+        }
+        with_hex_str(self.value, |s| {
+            let prefix = T::OPTIONS.prefix_str();
+            let s = truncate(s, Some(self.visible.min(s.len() / 2)));
+            pad(
+                f,
+                prefix,
+                Grouped::new(s),
+                "\u{2026}[redacted]",
+                fmt::Alignment::Left,
+                false,
+            )
+        })
+    }
+}
+
+/// Truncate `digits` to at most `precision` characters.
+fn truncate(digits: &str, precision: Option<usize>) -> &str {
+    match precision.and_then(|precision| digits.char_indices().nth(precision)) {
+        Some((i, _)) => &digits[..i],
+        None => digits,
+    }
+}
+
+/// Hexadecimal digits split into groups.
+struct Grouped<'a> {
+    hex: &'a str,
+
+    /// The number of digits per group (`0` disables grouping).
+    group: usize,
+
+    separator: char,
+}
+
+impl<'a> Grouped<'a> {
+    const fn new(hex: &'a str) -> Self {
+        Self {
+            hex,
+            group: 0,
+            separator: ' ',
+        }
+    }
+
+    /// The number of characters including separators.
+    const fn len(&self) -> usize {
+        let separators = match self.group {
+            0 => 0,
+            group => self.hex.len().saturating_sub(1) / group,
+        };
+        self.hex.len() + separators
+    }
+
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+
+        if self.group == 0 {
+            return f.write_str(self.hex);
+        }
+        for (i, chunk) in self.hex.as_bytes().chunks(self.group).enumerate() {
+            if i != 0 {
+                f.write_char(self.separator)?;
+            }
+            // the digits are ascii only, so every chunk is valid UTF-8
+            f.write_str(core::str::from_utf8(chunk).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the `prefix`, `digits` and `suffix` honouring all options of the formatter.
+///
+/// Zero-padding (`{:08x}`) is only applied if `zero_pad` is set (the numeric formats),
+/// otherwise it is ignored like by `Formatter::pad`.
+fn pad(
+    f: &mut fmt::Formatter,
+    prefix: &str,
+    mut digits: Grouped,
+    suffix: &str,
+    align: fmt::Alignment,
+    zero_pad: bool,
+) -> fmt::Result {
+    use fmt::Write;
+
+    digits.hex = truncate(digits.hex, f.precision());
+    let len = prefix.chars().count() + digits.len() + suffix.chars().count();
+    let padding = match f.width() {
+        Some(width) if width > len => width - len,
+        _ => {
+            f.write_str(prefix)?;
+            digits.write(f)?;
+            return f.write_str(suffix);
+        }
+    };
+    if zero_pad && f.sign_aware_zero_pad() {
+        f.write_str(prefix)?;
+        for _ in 0..padding {
+            f.write_char('0')?;
+        }
+        digits.write(f)?;
+        return f.write_str(suffix);
+    }
+    let (before, after) = match f.align().unwrap_or(align) {
+        fmt::Alignment::Left => (0, padding),
+        fmt::Alignment::Right => (padding, 0),
+        fmt::Alignment::Center => (padding / 2, padding.div_ceil(2)),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(prefix)?;
+    digits.write(f)?;
+    f.write_str(suffix)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "zeroize", feature = "std"))]
+mod tests {
+    use super::{FromHexCore, Options, ToHexCore};
+    use crate::{FromHex, FromHexError, ToHex};
+    use std::{cell::RefCell, vec, vec::Vec};
+
+    std::thread_local!(static DROPPED: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) });
+
+    /// Records its contents when dropped.
+    struct Tracked(Vec<u8>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.borrow_mut().push(self.0.clone()));
+        }
+    }
+
+    /// Check that buffers were dropped since the last call and all of them were wiped.
+    fn wiped() -> bool {
+        let dropped = DROPPED.with(|dropped| dropped.replace(Vec::new()));
+        !dropped.is_empty() && dropped.iter().flatten().all(|&b| b == 0)
+    }
+
+    struct Secret([u8; 4]);
+
+    const OPTIONS: Options = Options {
+        zeroize: true,
+        ..Options::DEFAULT
+    };
+
+    impl ToHexCore for Secret {
+        type Bytes = Tracked;
+        type Buffer = Tracked;
+        const OPTIONS: Options = OPTIONS;
+        fn create_buffer(len: usize) -> Tracked {
+            Tracked(vec![0; len])
+        }
+        fn buffer_as_bytes(buffer: &mut Tracked) -> &mut [u8] {
+            &mut buffer.0
+        }
+        fn to_binary_bytes(&self) -> Tracked {
+            Tracked(self.0.to_vec())
+        }
+        fn binary_bytes_as_mut(bytes: &mut Tracked) -> &mut [u8] {
+            &mut bytes.0
+        }
+        fn as_binary_bytes<'a>(&'a self, bytes: &'a Tracked) -> &'a [u8] {
+            &bytes.0
+        }
+    }
+
+    impl ToHex for Secret {}
+
+    impl FromHexCore for Secret {
+        type Bytes = Tracked;
+        type Error = FromHexError;
+        const OPTIONS: Options = OPTIONS;
+        const TYPE_NAME: &'static str = "Secret";
+        fn create_bytes(_len: Option<usize>) -> Tracked {
+            Tracked(vec![0; 4])
+        }
+        fn bytes_as_mut(bytes: &mut Tracked) -> &mut [u8] {
+            &mut bytes.0
+        }
+        fn from_binary_bytes(mut bytes: Tracked) -> Result<Self, FromHexError> {
+            let mut data = [0; 4];
+            data.copy_from_slice(&bytes.0);
+            zeroize::Zeroize::zeroize(bytes.0.as_mut_slice());
+            Ok(Self(data))
+        }
+    }
+
+    impl FromHex for Secret {}
+
+    #[test]
+    fn zeroize_encode() {
+        assert_eq!(Secret([0xa1, 0xb2, 0xc3, 0xd4]).to_hex(), "a1b2c3d4");
+        assert!(wiped());
+        super::with_hex_str(&Secret([0xa1, 0xb2, 0xc3, 0xd4]), |hex| {
+            assert_eq!(hex, "a1b2c3d4");
+        });
+        assert!(wiped());
+    }
+
+    // the decoded bytes are owned (and wiped) by `from_binary_bytes`
+    #[test]
+    fn zeroize_decode() {
+        let secret = Secret::from_hex(b"a1b2c3d4").unwrap();
+        assert_eq!(secret.0, [0xa1, 0xb2, 0xc3, 0xd4]);
+        assert!(wiped());
+    }
+
+    #[test]
+    fn zeroize_decode_error() {
+        assert!(Secret::from_hex(b"a1b2c3dg").is_err());
+        assert!(wiped());
+    }
 }
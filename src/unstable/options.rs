@@ -0,0 +1,155 @@
+/// Options for the hexadecimal representation of a type.
+///
+/// The options of a type are set using the presets of the `impl_hex` macros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// each flag corresponds to an independent preset
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    /// Use uppercase hexadecimal digits (`uppercase` preset).
+    pub uppercase: bool,
+
+    /// How a `0x` prefix is handled while parsing (`prefix`, `prefix(optional)` and
+    /// `optional_prefix` presets).
+    pub prefix: Prefix,
+
+    /// Add a `0x` prefix to the hexadecimal representation (`prefix` and `prefix(optional)`
+    /// presets).
+    pub emit_prefix: bool,
+
+    /// Ignore whitespace and `separators` while parsing (`lenient` and `lenient(b"...")`
+    /// presets).
+    pub lenient: bool,
+
+    /// The separators ignored while parsing in lenient mode (`lenient(b"...")` preset).
+    pub separators: &'static [u8],
+
+    /// The number of bytes per group in `Display` (`0` disables grouping, `group` preset).
+    pub group: usize,
+
+    /// The separator between groups in `Display` (`group` preset).
+    pub separator: char,
+
+    /// Reverse the byte order of the hexadecimal representation (`reversed` preset).
+    ///
+    /// The binary representation (e.g. used by binary serde formats) is not affected.
+    pub reversed: bool,
+
+    /// Use and verify an EIP-55 mixed-case checksum (`checksum` and `optional_checksum` presets).
+    ///
+    /// This takes precedence over `uppercase`.
+    pub checksum: Checksum,
+
+    /// Reject digits that are not in the case given by `uppercase` while parsing (`strict`
+    /// preset).
+    ///
+    /// Only the canonical form is accepted: `lenient` is ignored and a lowercase `0x` prefix is
+    /// required if (and only if) `emit_prefix` is set. The case is not checked if a `checksum`
+    /// is used.
+    pub strict: bool,
+
+    /// Encode and decode without secret-dependent branches or table lookups (`constant_time`
+    /// preset).
+    ///
+    /// `lenient` is ignored while parsing. The presets cannot be combined with `lenient` or a
+    /// `checksum`, which are not implemented in constant time.
+    pub constant_time: bool,
+
+    /// Wipe the temporary binary and hexadecimal buffers after use (`zeroize` preset).
+    ///
+    /// This is ignored if the `zeroize` feature is not enabled (the preset is rejected).
+    pub zeroize: bool,
+}
+
+impl Options {
+    /// The default options (lowercase, without a prefix).
+    pub const DEFAULT: Self = Self {
+        uppercase: false,
+        prefix: Prefix::Forbidden,
+        emit_prefix: false,
+        lenient: false,
+        separators: Self::SEPARATORS,
+        group: 0,
+        separator: ' ',
+        reversed: false,
+        checksum: Checksum::Disabled,
+        strict: false,
+        constant_time: false,
+        zeroize: false,
+    };
+
+    /// The default separators ignored in lenient mode.
+    pub const SEPARATORS: &'static [u8] = b":-_";
+
+    /// Check that the options can be combined (used by the presets).
+    ///
+    /// # Panics
+    /// Panics (a compile error if used in a `const` item) if `strict` is combined with
+    /// `lenient` or an optional prefix, or `constant_time` is combined with `lenient` or a
+    /// `checksum`.
+    #[must_use]
+    pub const fn validated(self) -> Self {
+        assert!(
+            !self.strict || !self.lenient,
+            "the `strict` preset cannot be combined with `lenient`"
+        );
+        assert!(
+            !self.strict || !matches!(self.prefix, Prefix::Optional),
+            "the `strict` preset cannot be combined with an optional prefix"
+        );
+        assert!(
+            !self.constant_time || !self.lenient,
+            "the `constant_time` preset cannot be combined with `lenient`"
+        );
+        assert!(
+            !self.constant_time || matches!(self.checksum, Checksum::Disabled),
+            "the `constant_time` preset cannot be combined with a checksum"
+        );
+        self
+    }
+
+    /// The prefix added to the hexadecimal representation.
+    #[must_use]
+    pub const fn prefix_str(&self) -> &'static str {
+        if self.emit_prefix {
+            "0x"
+        } else {
+            ""
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How a `0x` (or `0X`) prefix is handled while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefix {
+    /// The prefix is not allowed.
+    Forbidden,
+
+    /// The prefix is allowed, but not required.
+    Optional,
+
+    /// The prefix is required.
+    Required,
+}
+
+/// How an EIP-55 mixed-case checksum is handled.
+///
+/// The case of each letter in the hexadecimal representation is given by the Keccak-256 hash
+/// of the lowercase representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// No checksum is used, the case is not verified.
+    Disabled,
+
+    /// The checksum is used, but all-lowercase and all-uppercase input is accepted without
+    /// verifying the checksum.
+    Optional,
+
+    /// The checksum is used and always verified.
+    Required,
+}
@@ -1,10 +1,10 @@
 //! Functions required to implement serde traits.
 
-use super::encode_hex;
-use crate::{FromHex, FromHexError, ParseHex, ToHex};
+use super::{bytes_from_hex_options, encode_hex_options, zeroize, Length};
+use crate::{FromHex, FromHexError, ToHex};
 use core::{fmt, marker::PhantomData};
 use serde::{
-    de::{Error, SeqAccess, Unexpected, Visitor},
+    de::{Error, Expected, SeqAccess, Unexpected, Visitor},
     ser::SerializeTupleStruct,
     Deserializer, Serializer,
 };
@@ -29,14 +29,30 @@ where
     S: Serializer,
     T: ToHex + Metadata,
 {
-    let bytes = value.to_binary_bytes();
-    let bytes = value.as_binary_bytes(&bytes);
+    let mut bytes = value.to_binary_bytes();
+    let result = serialize_binary_bytes::<S, T>(serializer, value.as_binary_bytes(&bytes));
+    zeroize(&T::OPTIONS, T::binary_bytes_as_mut(&mut bytes));
+    result
+}
+
+fn serialize_binary_bytes<S, T>(serializer: S, bytes: &[u8]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ToHex + Metadata,
+{
     if serializer.is_human_readable() {
         let mut buffer = T::create_buffer(bytes.len() * 2);
-        let buffer = T::buffer_as_bytes(&mut buffer);
-        serializer.serialize_str(encode_hex(bytes, buffer))
-    } else {
+        let hex = encode_hex_options(bytes, T::buffer_as_bytes(&mut buffer), &T::OPTIONS);
+        let result = match T::OPTIONS.prefix_str() {
+            "" => serializer.serialize_str(hex),
+            prefix => serializer.collect_str(&format_args!("{prefix}{hex}")),
+        };
+        zeroize(&T::OPTIONS, T::buffer_as_bytes(&mut buffer));
+        result
+    } else if T::LEN.is_some() {
         serialize_bytes(serializer, T::NAME, bytes)
+    } else {
+        serializer.serialize_bytes(bytes)
     }
 }
 
@@ -56,16 +72,14 @@ pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
     T: FromHex + Metadata,
+    T::Error: fmt::Display,
 {
     if deserializer.is_human_readable() {
         deserializer.deserialize_str(HexVisitor::new())
     } else if let Some(len) = T::LEN {
         deserializer.deserialize_tuple_struct(T::NAME, len, BinaryVisitor::new())
     } else {
-        Err(D::Error::custom(format_args!(
-            "the type {} does not have a known length",
-            T::NAME,
-        )))
+        deserializer.deserialize_byte_buf(BinaryVisitor::new())
     }
 }
 
@@ -80,22 +94,24 @@ impl<T> HexVisitor<T>
 where
     T: FromHex,
 {
-    fn new() -> Self {
+    const fn new() -> Self {
         Self { seed: PhantomData }
     }
 }
 
-impl<'de, T> Visitor<'de> for HexVisitor<T>
+impl<T> Visitor<'_> for HexVisitor<T>
 where
     T: FromHex + Metadata,
+    T::Error: fmt::Display,
 {
     type Value = T;
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str(T::EXPECTING)
     }
     fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
-        v.parse_hex()
-            .map_err(|err| err.into_serde(Unexpected::Str(v), &self))
+        let bytes = bytes_from_hex_options::<T>(v.as_bytes(), &T::OPTIONS, T::OPTIONS.separators)
+            .map_err(|err| err.into_serde(Unexpected::Str(v), &self))?;
+        T::from_binary_bytes(bytes).map_err(E::custom)
     }
 }
 
@@ -110,7 +126,7 @@ impl<T> BinaryVisitor<T>
 where
     T: FromHex,
 {
-    fn new() -> Self {
+    const fn new() -> Self {
         Self { seed: PhantomData }
     }
 }
@@ -118,6 +134,7 @@ where
 impl<'de, T> Visitor<'de> for BinaryVisitor<T>
 where
     T: FromHex + Metadata,
+    T::Error: fmt::Display,
 {
     type Value = T;
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -128,33 +145,75 @@ where
         A: SeqAccess<'de>,
     {
         let len = seq.size_hint();
+        if let Some(len) = len {
+            if !T::LENGTH.contains(len) {
+                return Err(Error::invalid_length(len, &self));
+            }
+        }
         let mut bytes = T::create_bytes(len);
-        let len = {
+        if let Err(err) = read_seq(&mut seq, T::bytes_as_mut(&mut bytes), len, &self) {
+            zeroize(&T::OPTIONS, T::bytes_as_mut(&mut bytes));
+            return Err(err);
+        }
+        T::from_binary_bytes(bytes).map_err(Error::custom)
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if !T::LENGTH.contains(v.len()) {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let mut bytes = T::create_bytes(Some(v.len()));
+        {
             let bytes = T::bytes_as_mut(&mut bytes);
-            for (i, byte) in bytes.iter_mut().enumerate() {
-                if let Some(b) = seq.next_element()? {
-                    *byte = b
-                } else {
-                    return Err(Error::invalid_length(i, &self));
-                }
+            if bytes.len() != v.len() {
+                return Err(E::invalid_length(v.len(), &self));
             }
-            len.unwrap_or_else(|| bytes.len())
-        };
-        if seq.next_element::<u8>()?.is_some() {
-            Err(Error::invalid_length(len, &self))
+            bytes.copy_from_slice(v);
+        }
+        T::from_binary_bytes(bytes).map_err(E::custom)
+    }
+}
+
+/// Read exactly `bytes.len()` bytes from `seq`.
+fn read_seq<'de, A, V>(
+    seq: &mut A,
+    bytes: &mut [u8],
+    len: Option<usize>,
+    visitor: &V,
+) -> Result<(), A::Error>
+where
+    A: SeqAccess<'de>,
+    V: Visitor<'de>,
+{
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        if let Some(b) = seq.next_element()? {
+            *byte = b;
         } else {
-            T::from_binary_bytes(bytes).map_err(|err| err.into_serde(Unexpected::Seq, &self))
+            return Err(Error::invalid_length(i, visitor));
         }
     }
+    if seq.next_element::<u8>()?.is_some() {
+        return Err(Error::invalid_length(len.unwrap_or(bytes.len()), visitor));
+    }
+    Ok(())
 }
 
 impl FromHexError {
     fn into_serde<'de, E: Error, V: Visitor<'de>>(self, unexp: Unexpected, visitor: &V) -> E {
         match self {
-            Self::InvalidLength(len) => E::invalid_length(len, visitor),
-            Self::InvalidHexCharacter(_, c) => {
-                E::invalid_value(Unexpected::Char(c.into()), visitor)
+            Self::InvalidLength {
+                name,
+                expected,
+                actual,
+            } => E::invalid_length(actual, &ExpectedLength { name, expected }),
+            Self::InvalidHexCharacter { character, .. } => {
+                E::invalid_value(Unexpected::Char(character), visitor)
             }
+            Self::InvalidCase { character, .. } => E::invalid_value(
+                Unexpected::Char(character),
+                &"a digit in the canonical case",
+            ),
+            Self::MissingPrefix => E::invalid_value(unexp, &"a value with a 0x prefix"),
+            Self::InvalidChecksum => E::invalid_value(unexp, &"a value with a valid checksum"),
             Self::InvalidValue => E::invalid_value(unexp, visitor),
             Self::CustomStr(msg) => E::invalid_value(unexp, &msg),
             #[cfg(feature = "alloc")]
@@ -162,3 +221,15 @@ impl FromHexError {
         }
     }
 }
+
+struct ExpectedLength {
+    name: &'static str,
+    expected: Length,
+}
+
+impl Expected for ExpectedLength {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.expected.digits().fmt_unit(formatter, "hex digits")?;
+        write!(formatter, " for {}", self.name)
+    }
+}
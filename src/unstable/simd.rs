@@ -0,0 +1,406 @@
+//! SIMD implementations of the hexadecimal encoding and decoding (`x86_64` only).
+//!
+//! These functions process the longest possible prefix of the input and return the number of
+//! bytes processed. The caller handles the remainder and invalid input (to get the exact error
+//! position) using the scalar implementation.
+
+// only unaligned loads and stores are used
+#![allow(clippy::cast_ptr_alignment)]
+
+#[cfg(target_arch = "x86_64")]
+#[allow(clippy::wildcard_imports)]
+use core::arch::x86_64::*;
+
+/// Encode a prefix of `value` into `buffer` and return the number of encoded bytes.
+///
+/// `buffer` must be twice as long as `value`.
+pub fn encode(value: &[u8], buffer: &mut [u8], table: &[u8; 16]) -> usize {
+    debug_assert_eq!(value.len() * 2, buffer.len());
+    #[cfg(target_arch = "x86_64")]
+    {
+        // SAFETY: the required target features are available
+        unsafe {
+            if has_avx2() {
+                encode_avx2(value, buffer, table)
+            } else if has_ssse3() {
+                encode_ssse3(value, buffer, table)
+            } else {
+                encode_sse2(value, buffer, table)
+            }
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = (value, buffer, table);
+        0
+    }
+}
+
+/// Decode a prefix of `value` into `buffer` and return the number of decoded bytes.
+///
+/// `value` must be twice as long as `buffer`. Decoding stops before the first chunk containing
+/// an invalid character.
+pub fn decode(value: &[u8], buffer: &mut [u8]) -> usize {
+    debug_assert_eq!(value.len(), buffer.len() * 2);
+    #[cfg(target_arch = "x86_64")]
+    {
+        // SAFETY: the required target features are available
+        unsafe {
+            if has_avx2() {
+                decode_avx2(value, buffer)
+            } else if has_ssse3() {
+                decode_ssse3(value, buffer)
+            } else {
+                decode_sse2(value, buffer)
+            }
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = (value, buffer);
+        0
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_avx2() -> bool {
+    std::is_x86_feature_detected!("avx2")
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_ssse3() -> bool {
+    std::is_x86_feature_detected!("ssse3")
+}
+
+// runtime detection requires `std`, fall back to the features enabled at compile time
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+const fn has_avx2() -> bool {
+    cfg!(target_feature = "avx2")
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+const fn has_ssse3() -> bool {
+    cfg!(target_feature = "ssse3")
+}
+
+#[cfg(target_arch = "x86_64")]
+const fn splat(b: u8) -> i8 {
+    b.cast_signed()
+}
+
+/// Convert nibbles to ASCII digits without a shuffle (`'0' + n`, plus `offset` if `n > 9`).
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn nibbles_to_ascii_sse2(nibbles: __m128i, offset: __m128i) -> __m128i {
+    let letters = _mm_and_si128(_mm_cmpgt_epi8(nibbles, _mm_set1_epi8(9)), offset);
+    _mm_add_epi8(_mm_add_epi8(nibbles, _mm_set1_epi8(splat(b'0'))), letters)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn encode_sse2(value: &[u8], buffer: &mut [u8], table: &[u8; 16]) -> usize {
+    let offset = _mm_set1_epi8(splat(table[10] - b'0' - 10));
+    let mask = _mm_set1_epi8(0x0f);
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(16).zip(buffer.chunks_exact_mut(32)) {
+        let v = _mm_loadu_si128(chunk.as_ptr().cast());
+        let high = nibbles_to_ascii_sse2(_mm_and_si128(_mm_srli_epi16(v, 4), mask), offset);
+        let low = nibbles_to_ascii_sse2(_mm_and_si128(v, mask), offset);
+        let out = out.as_mut_ptr().cast::<__m128i>();
+        _mm_storeu_si128(out, _mm_unpacklo_epi8(high, low));
+        _mm_storeu_si128(out.add(1), _mm_unpackhi_epi8(high, low));
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_ssse3(value: &[u8], buffer: &mut [u8], table: &[u8; 16]) -> usize {
+    let table = _mm_loadu_si128(table.as_ptr().cast());
+    let mask = _mm_set1_epi8(0x0f);
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(16).zip(buffer.chunks_exact_mut(32)) {
+        let v = _mm_loadu_si128(chunk.as_ptr().cast());
+        let high = _mm_shuffle_epi8(table, _mm_and_si128(_mm_srli_epi16(v, 4), mask));
+        let low = _mm_shuffle_epi8(table, _mm_and_si128(v, mask));
+        let out = out.as_mut_ptr().cast::<__m128i>();
+        _mm_storeu_si128(out, _mm_unpacklo_epi8(high, low));
+        _mm_storeu_si128(out.add(1), _mm_unpackhi_epi8(high, low));
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_avx2(value: &[u8], buffer: &mut [u8], table: &[u8; 16]) -> usize {
+    let digits = _mm256_broadcastsi128_si256(_mm_loadu_si128(table.as_ptr().cast()));
+    let mask = _mm256_set1_epi8(0x0f);
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(32).zip(buffer.chunks_exact_mut(64)) {
+        let v = _mm256_loadu_si256(chunk.as_ptr().cast());
+        let high = _mm256_shuffle_epi8(digits, _mm256_and_si256(_mm256_srli_epi16(v, 4), mask));
+        let low = _mm256_shuffle_epi8(digits, _mm256_and_si256(v, mask));
+        // the unpack instructions work on each 128-bit lane separately
+        let first = _mm256_unpacklo_epi8(high, low);
+        let second = _mm256_unpackhi_epi8(high, low);
+        let out = out.as_mut_ptr().cast::<__m256i>();
+        _mm256_storeu_si256(out, _mm256_permute2x128_si256(first, second, 0x20));
+        _mm256_storeu_si256(out.add(1), _mm256_permute2x128_si256(first, second, 0x31));
+        done += 32;
+    }
+    // encode the last 16 bytes (if available) using 128-bit vectors
+    done + encode_ssse3(&value[done..], &mut buffer[done * 2..], table)
+}
+
+/// Check that all characters are hexadecimal digits and convert them to nibbles.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn ascii_to_nibbles_sse2(c: __m128i) -> Option<__m128i> {
+    // the characters are compared as signed bytes, non-ASCII characters are negative
+    let in_range = |c, low: u8, high: u8| {
+        _mm_and_si128(
+            _mm_cmpgt_epi8(c, _mm_set1_epi8(splat(low - 1))),
+            _mm_cmplt_epi8(c, _mm_set1_epi8(splat(high + 1))),
+        )
+    };
+    let digit = in_range(c, b'0', b'9');
+    let lower = _mm_or_si128(c, _mm_set1_epi8(0x20));
+    let letter = in_range(lower, b'a', b'f');
+    if _mm_movemask_epi8(_mm_or_si128(digit, letter)) != 0xffff {
+        return None;
+    }
+    let digit = _mm_and_si128(digit, _mm_sub_epi8(c, _mm_set1_epi8(splat(b'0'))));
+    let letter = _mm_and_si128(letter, _mm_sub_epi8(lower, _mm_set1_epi8(splat(b'a' - 10))));
+    Some(_mm_or_si128(digit, letter))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn decode_sse2(value: &[u8], buffer: &mut [u8]) -> usize {
+    // combine the nibble pairs (high nibble in the low byte of each 16-bit lane)
+    let combine = |n| {
+        _mm_or_si128(
+            _mm_slli_epi16(_mm_and_si128(n, _mm_set1_epi16(0x00ff)), 4),
+            _mm_srli_epi16(n, 8),
+        )
+    };
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(32).zip(buffer.chunks_exact_mut(16)) {
+        let chunk = chunk.as_ptr().cast::<__m128i>();
+        let first = ascii_to_nibbles_sse2(_mm_loadu_si128(chunk));
+        let second = ascii_to_nibbles_sse2(_mm_loadu_si128(chunk.add(1)));
+        let (Some(first), Some(second)) = (first, second) else {
+            break;
+        };
+        let bytes = _mm_packus_epi16(combine(first), combine(second));
+        _mm_storeu_si128(out.as_mut_ptr().cast(), bytes);
+        done += 16;
+    }
+    done
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_ssse3(value: &[u8], buffer: &mut [u8]) -> usize {
+    // multiply the high nibbles by 16 and add the low nibbles
+    let factors = _mm_set1_epi16(0x0110);
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(32).zip(buffer.chunks_exact_mut(16)) {
+        let chunk = chunk.as_ptr().cast::<__m128i>();
+        let first = ascii_to_nibbles_sse2(_mm_loadu_si128(chunk));
+        let second = ascii_to_nibbles_sse2(_mm_loadu_si128(chunk.add(1)));
+        let (Some(first), Some(second)) = (first, second) else {
+            break;
+        };
+        let bytes = _mm_packus_epi16(
+            _mm_maddubs_epi16(first, factors),
+            _mm_maddubs_epi16(second, factors),
+        );
+        _mm_storeu_si128(out.as_mut_ptr().cast(), bytes);
+        done += 16;
+    }
+    done
+}
+
+/// Check that all characters are hexadecimal digits and convert them to nibbles.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn ascii_to_nibbles_avx2(c: __m256i) -> Option<__m256i> {
+    let in_range = |c, low: u8, high: u8| {
+        _mm256_and_si256(
+            _mm256_cmpgt_epi8(c, _mm256_set1_epi8(splat(low - 1))),
+            _mm256_cmpgt_epi8(_mm256_set1_epi8(splat(high + 1)), c),
+        )
+    };
+    let digit = in_range(c, b'0', b'9');
+    let lower = _mm256_or_si256(c, _mm256_set1_epi8(0x20));
+    let letter = in_range(lower, b'a', b'f');
+    if _mm256_movemask_epi8(_mm256_or_si256(digit, letter)) != -1 {
+        return None;
+    }
+    let digit = _mm256_and_si256(digit, _mm256_sub_epi8(c, _mm256_set1_epi8(splat(b'0'))));
+    let letter = _mm256_and_si256(
+        letter,
+        _mm256_sub_epi8(lower, _mm256_set1_epi8(splat(b'a' - 10))),
+    );
+    Some(_mm256_or_si256(digit, letter))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_avx2(value: &[u8], buffer: &mut [u8]) -> usize {
+    let factors = _mm256_set1_epi16(0x0110);
+    let mut done = 0;
+    for (chunk, out) in value.chunks_exact(64).zip(buffer.chunks_exact_mut(32)) {
+        let chunk = chunk.as_ptr().cast::<__m256i>();
+        let first = ascii_to_nibbles_avx2(_mm256_loadu_si256(chunk));
+        let second = ascii_to_nibbles_avx2(_mm256_loadu_si256(chunk.add(1)));
+        let (Some(first), Some(second)) = (first, second) else {
+            return done;
+        };
+        let bytes = _mm256_packus_epi16(
+            _mm256_maddubs_epi16(first, factors),
+            _mm256_maddubs_epi16(second, factors),
+        );
+        // the pack instruction works on each 128-bit lane separately
+        let bytes = _mm256_permute4x64_epi64(bytes, 0b11_01_10_00);
+        _mm256_storeu_si256(out.as_mut_ptr().cast(), bytes);
+        done += 32;
+    }
+    // decode the last 16 bytes (if available) using 128-bit vectors
+    done + decode_ssse3(&value[done * 2..], &mut buffer[done..])
+}
+
+#[cfg(all(test, target_arch = "x86_64", feature = "std"))]
+mod tests {
+    use std::vec::Vec;
+
+    type Encoder = unsafe fn(&[u8], &mut [u8], &[u8; 16]) -> usize;
+    type Decoder = unsafe fn(&[u8], &mut [u8]) -> usize;
+
+    /// Bytes that are not hexadecimal digits (including the neighbours of the digit ranges and
+    /// non-ASCII bytes that are digits if the highest bit is cleared).
+    const INVALID: [u8; 14] = [
+        b'/', b':', b'@', b'G', b'`', b'g', b' ', 0, 0x7f, 0x80, 0xb0, 0xc1, 0xe1, 0xff,
+    ];
+
+    fn encoders() -> Vec<(&'static str, Encoder)> {
+        let mut encoders: Vec<(&'static str, Encoder)> = vec![("sse2", super::encode_sse2)];
+        if super::has_ssse3() {
+            encoders.push(("ssse3", super::encode_ssse3));
+        }
+        if super::has_avx2() {
+            encoders.push(("avx2", super::encode_avx2));
+        }
+        encoders
+    }
+
+    fn decoders() -> Vec<(&'static str, Decoder)> {
+        let mut decoders: Vec<(&'static str, Decoder)> = vec![("sse2", super::decode_sse2)];
+        if super::has_ssse3() {
+            decoders.push(("ssse3", super::decode_ssse3));
+        }
+        if super::has_avx2() {
+            decoders.push(("avx2", super::decode_avx2));
+        }
+        decoders
+    }
+
+    /// A deterministic pseudo-random byte sequence.
+    fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()[0]
+            })
+            .collect()
+    }
+
+    fn scalar_encode(value: &[u8], table: &[u8; 16]) -> Vec<u8> {
+        value
+            .iter()
+            .flat_map(|&b| [table[usize::from(b >> 4)], table[usize::from(b & 0xf)]])
+            .collect()
+    }
+
+    /// Decode byte by byte, returning the position of the first invalid character on errors.
+    fn scalar_decode(value: &[u8]) -> Result<Vec<u8>, usize> {
+        let nibble = |i: usize| match value[i] {
+            c @ b'0'..=b'9' => Ok(c - b'0'),
+            c @ b'a'..=b'f' => Ok(c - b'a' + 10),
+            c @ b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(i),
+        };
+        (0..value.len() / 2)
+            .map(|i| Ok((nibble(i * 2)? << 4) | nibble(i * 2 + 1)?))
+            .collect()
+    }
+
+    /// Decode a prefix using `decoder` and the remainder using the scalar implementation.
+    fn decode_with(decoder: Decoder, value: &[u8]) -> Result<Vec<u8>, usize> {
+        let mut buffer = vec![0; value.len() / 2];
+        // SAFETY: only decoders supported by this CPU are tested
+        let done = unsafe { decoder(value, &mut buffer) };
+        assert!(done <= buffer.len());
+        // the bytes decoded so far must be correct, even if the remainder is invalid
+        assert_eq!(
+            Ok(&buffer[..done]),
+            scalar_decode(&value[..done * 2]).as_deref()
+        );
+        let rest = scalar_decode(&value[done * 2..]).map_err(|i| done * 2 + i)?;
+        buffer[done..].copy_from_slice(&rest);
+        Ok(buffer)
+    }
+
+    #[test]
+    fn encode_matches_scalar() {
+        for (name, encoder) in encoders() {
+            for table in [&super::super::hex::LOWER, &super::super::hex::UPPER] {
+                for len in 0..200 {
+                    let value = random_bytes(len, len as u64);
+                    let mut buffer = vec![0; len * 2];
+                    // SAFETY: only encoders supported by this CPU are tested
+                    let done = unsafe { encoder(&value, &mut buffer, table) };
+                    assert!(done <= len, "{}: {}", name, len);
+                    let expected = scalar_encode(&value, table);
+                    assert_eq!(buffer[..done * 2], expected[..done * 2], "{name}: {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decode_matches_scalar() {
+        for (name, decoder) in decoders() {
+            for len in 0..200 {
+                let value = random_bytes(len, len as u64);
+                let mut hex = scalar_encode(&value, &super::super::hex::LOWER);
+                // mix lowercase and uppercase digits
+                for (c, r) in hex.iter_mut().zip(random_bytes(len * 2, !(len as u64))) {
+                    if r & 1 == 1 {
+                        c.make_ascii_uppercase();
+                    }
+                }
+                assert_eq!(decode_with(decoder, &hex), Ok(value), "{name}: {len}");
+                for position in 0..hex.len() {
+                    // every invalid byte is tested at many positions of every chunk
+                    for i in [position, position / 2 + len] {
+                        let invalid = INVALID[i % INVALID.len()];
+                        let mut hex = hex.clone();
+                        hex[position] = invalid;
+                        assert_eq!(
+                            decode_with(decoder, &hex),
+                            Err(position),
+                            "{name}: {len} {position} {invalid:#x}",
+                        );
+                    }
+                }
+            }
+        }
+    }
+}